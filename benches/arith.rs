@@ -0,0 +1,93 @@
+//! Timings for the arithmetic that was optimized, each next to the approach
+//! it replaced. Reports time per call, nothing is asserted:
+//!
+//! cargo bench --bench arith
+
+#![allow(clippy::needless_return)]
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use eccsecp256k1::modctx::ModCtx;
+use eccsecp256k1::secp256k1::{EccPoint, FieldP, SECP256K1};
+use eccsecp256k1::u256::U256;
+
+/// Each measurement runs for at least this long.
+const MIN_TIME: Duration = Duration::from_millis(200);
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    black_box(f());
+
+    let (start, mut iters) = (Instant::now(), 0u32);
+    while start.elapsed() < MIN_TIME {
+        black_box(f());
+        iters += 1;
+    }
+    println!("{:<32} {:>12.2?}", name, start.elapsed() / iters);
+}
+
+/// The double-and-add `mul_mod` replaced by the widening multiply.
+fn mul_mod_double_and_add(a: &U256, b: &U256, p: &U256) -> U256 {
+    let mut r = U256::zero();
+    for i in (0..b.bit_len()).rev() {
+        r = r.add_mod(&r, p);
+        if b.bit(i) {
+            r = r.add_mod(a, p);
+        }
+    }
+    return r;
+}
+
+/// `pr_to_pub` as it was before `FieldP`, through a fresh `ModCtx` for
+/// every point operation.
+fn pr_to_pub_modctx(k: &U256) -> EccPoint {
+    let add = |a: &EccPoint, b: &EccPoint| {
+        if a.is_zero_point() {
+            return *b;
+        }
+        let f = ModCtx::new(SECP256K1::p()).expect("odd-modulus");
+        let (dx, dy) = if a.x == b.x {
+            (
+                f.mul(&a.y, &U256::new(2)),
+                f.mul(&f.mul(&a.x, &a.x), &U256::new(3)),
+            )
+        } else {
+            (f.sub(&a.x, &b.x), f.sub(&a.y, &b.y))
+        };
+        let lambda = f.mul(&dy, &f.inv(&dx).expect("invertible"));
+        let x = f.sub(&f.sub(&f.mul(&lambda, &lambda), &a.x), &b.x);
+        let y = f.sub(&f.mul(&f.sub(&a.x, &x), &lambda), &a.y);
+        return EccPoint { x, y };
+    };
+
+    let mut r = SECP256K1::zero_point();
+    for i in (0..k.bit_len()).rev() {
+        r = add(&r, &r);
+        if k.bit(i) {
+            r = add(&r, &SECP256K1::g());
+        }
+    }
+    return r;
+}
+
+fn main() {
+    let p = SECP256K1::p();
+    let a = U256::from_be_bytes(&[0x5c; 32]);
+    let b = U256::from_be_bytes(&[0x3d; 32]);
+
+    bench("mul_mod (widening)", || a.mul_mod(&b, &p));
+    bench("mul_mod (double-and-add)", || {
+        mul_mod_double_and_add(&a, &b, &p)
+    });
+
+    let field = SECP256K1::field();
+    bench("FieldP::mul", || FieldP.mul(&a, &b));
+    bench("ModCtx::mul, mod p", || field.mul(&a, &b));
+
+    let k = U256::from_be_bytes(&[0xa7; 32]);
+    bench("pr_to_pub (FieldP)", || SECP256K1::pr_to_pub(&k));
+    bench("pr_to_pub (ModCtx)", || pr_to_pub_modctx(&k));
+
+    bench("inv_mod (extended Euclid)", || a.inv_mod(&p));
+    bench("inv by Fermat, a^(p - 2)", || U256::one().div_mod(&a, &p));
+}
@@ -0,0 +1,54 @@
+//! Stores a new case in the regression corpus replayed by tests/corpus.rs:
+//!
+//! cargo run --example add_corpus -- <decoder> <hexfile>
+//!
+//! The hex is normalized (whitespace and a 0x prefix dropped, lowercased)
+//! and written to tests/corpus/<decoder>/, named after its keccak256, so
+//! adding the same case twice is a no-op.
+
+#![allow(clippy::needless_return)]
+
+use std::path::Path;
+use std::{env, fs, process};
+
+use eccsecp256k1::crypto::hashing::hash_keccak256_bytes;
+
+/// Same cap as tests/corpus.rs.
+const MAX_INPUT_LEN: usize = 4096;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() != 2 {
+        eprintln!("usage: add_corpus <decoder> <hexfile>");
+        process::exit(2);
+    }
+    let (decoder, hexfile) = (&args[0], &args[1]);
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/corpus")
+        .join(decoder);
+    if !dir.is_dir() {
+        eprintln!("no corpus directory for decoder {:?}", decoder);
+        process::exit(1);
+    }
+
+    let text = fs::read_to_string(hexfile).unwrap_or_else(|e| {
+        eprintln!("{}: {}", hexfile, e);
+        process::exit(1);
+    });
+    let hex: String = text.split_whitespace().collect();
+    let hex = hex.strip_prefix("0x").unwrap_or(&hex).to_ascii_lowercase();
+    let input = hex::decode(&hex).unwrap_or_else(|e| {
+        eprintln!("{}: {}", hexfile, e);
+        process::exit(1);
+    });
+    if input.len() > MAX_INPUT_LEN {
+        eprintln!("{} bytes, over the {}-byte cap", input.len(), MAX_INPUT_LEN);
+        process::exit(1);
+    }
+
+    let name = hex::encode(&hash_keccak256_bytes(&input)[..8]);
+    let path = dir.join(format!("{}.hex", name));
+    fs::write(&path, format!("{}\n", hex)).expect("write-corpus");
+    println!("{}", path.display());
+}
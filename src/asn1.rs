@@ -0,0 +1,345 @@
+//! Minimal DER (distinguished encoding rules) reader and writer, just enough
+//! for the structures this crate parses. Everything is length-checked:
+//! indefinite lengths, non-minimal lengths and truncated input are errors,
+//! never panics.
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DerError {
+    UnexpectedEnd,
+    UnexpectedTag {
+        expected: u8,
+        found: u8,
+    },
+    IndefiniteLength,
+    NonMinimalLength,
+    /// Length field longer than 4 bytes.
+    LengthTooLarge,
+    /// Negative, empty, or non-minimally encoded INTEGER.
+    InvalidInteger,
+    /// Unused-bits byte above 7, or set on an empty BIT STRING.
+    InvalidBitString,
+    TrailingData,
+}
+
+pub struct DerReader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        return DerReader { input };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.input.is_empty();
+    }
+
+    /// Reads one element with the given tag and returns its contents.
+    pub fn read_element(&mut self, tag: u8) -> Result<&'a [u8], DerError> {
+        let (&found, rest) = self.input.split_first().ok_or(DerError::UnexpectedEnd)?;
+        if found != tag {
+            return Err(DerError::UnexpectedTag {
+                expected: tag,
+                found,
+            });
+        }
+
+        let (&first, mut rest) = rest.split_first().ok_or(DerError::UnexpectedEnd)?;
+        let len = match first {
+            n if n < 0x80 => n as usize,
+            0x80 => return Err(DerError::IndefiniteLength),
+            n => {
+                let count = (n & 0x7f) as usize;
+                if count > 4 {
+                    return Err(DerError::LengthTooLarge);
+                }
+                if rest.len() < count {
+                    return Err(DerError::UnexpectedEnd);
+                }
+
+                let (bytes, after) = rest.split_at(count);
+                rest = after;
+                // Long form only for lengths >= 128, without leading zeros
+                if bytes[0] == 0 {
+                    return Err(DerError::NonMinimalLength);
+                }
+                let len = bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+                if len < 0x80 {
+                    return Err(DerError::NonMinimalLength);
+                }
+                len
+            }
+        };
+
+        if rest.len() < len {
+            return Err(DerError::UnexpectedEnd);
+        }
+
+        let (contents, after) = rest.split_at(len);
+        self.input = after;
+        return Ok(contents);
+    }
+
+    /// Reads a SEQUENCE and returns a reader over its contents.
+    pub fn read_sequence(&mut self) -> Result<DerReader<'a>, DerError> {
+        return Ok(DerReader::new(self.read_element(TAG_SEQUENCE)?));
+    }
+
+    /// Reads a non-negative INTEGER and returns its big-endian magnitude
+    /// (without the 0x00 sign byte).
+    pub fn read_integer(&mut self) -> Result<&'a [u8], DerError> {
+        let contents = self.read_element(TAG_INTEGER)?;
+        match contents {
+            [] => return Err(DerError::InvalidInteger),
+            [b, ..] if b & 0x80 != 0 => return Err(DerError::InvalidInteger),
+            [0, b, ..] if b & 0x80 == 0 => return Err(DerError::InvalidInteger),
+            [0, rest @ ..] if !rest.is_empty() => return Ok(rest),
+            _ => return Ok(contents),
+        }
+    }
+
+    /// Reads a BIT STRING and returns the unused-bits count and the data.
+    pub fn read_bit_string(&mut self) -> Result<(u8, &'a [u8]), DerError> {
+        let contents = self.read_element(TAG_BIT_STRING)?;
+        let (&unused, data) = contents.split_first().ok_or(DerError::InvalidBitString)?;
+        if unused > 7 || (data.is_empty() && unused != 0) {
+            return Err(DerError::InvalidBitString);
+        }
+
+        return Ok((unused, data));
+    }
+
+    /// Reads an OBJECT IDENTIFIER and returns its encoded contents, to be
+    /// compared against a known OID.
+    pub fn read_oid(&mut self) -> Result<&'a [u8], DerError> {
+        return self.read_element(TAG_OID);
+    }
+
+    pub fn expect_end(&self) -> Result<(), DerError> {
+        if !self.input.is_empty() {
+            return Err(DerError::TrailingData);
+        }
+        return Ok(());
+    }
+}
+
+#[derive(Default)]
+pub struct DerWriter {
+    buf: Vec<u8>,
+}
+
+impl DerWriter {
+    pub fn new() -> Self {
+        return DerWriter { buf: vec![] };
+    }
+
+    pub fn write_element(&mut self, tag: u8, contents: &[u8]) {
+        self.buf.push(tag);
+
+        let len = contents.len();
+        if len < 0x80 {
+            self.buf.push(len as u8);
+        } else {
+            let bytes = (len as u32).to_be_bytes();
+            let skip = bytes.iter().take_while(|b| **b == 0).count();
+            self.buf.push(0x80 | (4 - skip) as u8);
+            self.buf.extend(&bytes[skip..]);
+        }
+
+        self.buf.extend(contents);
+    }
+
+    /// Writes a SEQUENCE whose contents are produced by `f`.
+    pub fn write_sequence<F: FnOnce(&mut DerWriter)>(&mut self, f: F) {
+        let mut inner = DerWriter::new();
+        f(&mut inner);
+        self.write_element(TAG_SEQUENCE, &inner.buf);
+    }
+
+    /// Writes a non-negative INTEGER from its big-endian magnitude, adding
+    /// or stripping leading zeros as needed.
+    pub fn write_integer(&mut self, magnitude: &[u8]) {
+        let skip = magnitude.iter().take_while(|b| **b == 0).count();
+        let digits = &magnitude[skip..];
+
+        let mut contents = vec![];
+        if digits.first().is_none_or(|b| b & 0x80 != 0) {
+            contents.push(0);
+        }
+        contents.extend(digits);
+
+        self.write_element(TAG_INTEGER, &contents);
+    }
+
+    pub fn write_bit_string(&mut self, unused: u8, data: &[u8]) {
+        let mut contents = vec![unused];
+        contents.extend(data);
+        self.write_element(TAG_BIT_STRING, &contents);
+    }
+
+    pub fn write_oid(&mut self, contents: &[u8]) {
+        self.write_element(TAG_OID, contents);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        return self.buf;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asn1::*;
+    use crate::rng::TestRng;
+    use rand::RngCore;
+
+    #[test]
+    fn der_length_boundaries() {
+        for (len, header) in [
+            (0, vec![0x04, 0x00]),
+            (127, vec![0x04, 0x7f]),
+            (128, vec![0x04, 0x81, 0x80]),
+            (255, vec![0x04, 0x81, 0xff]),
+            (256, vec![0x04, 0x82, 0x01, 0x00]),
+            (65536, vec![0x04, 0x83, 0x01, 0x00, 0x00]),
+        ] {
+            let contents = vec![0xab; len];
+
+            let mut w = DerWriter::new();
+            w.write_element(0x04, &contents);
+            let der = w.finish();
+            assert_eq!(&der[..header.len()], &header[..], "{}", len);
+            assert_eq!(der.len(), header.len() + len);
+
+            let mut r = DerReader::new(&der);
+            assert_eq!(r.read_element(0x04).unwrap(), &contents[..]);
+            r.expect_end().unwrap();
+
+            // One byte short
+            let mut r = DerReader::new(&der[..der.len() - 1]);
+            assert_eq!(r.read_element(0x04), Err(DerError::UnexpectedEnd));
+        }
+    }
+
+    #[test]
+    fn der_rejects_bad_lengths() {
+        let cases: [(&[u8], DerError); 5] = [
+            (&[0x04, 0x80, 0x00, 0x00], DerError::IndefiniteLength),
+            (
+                &[0x04, 0x81, 0x05, 0, 0, 0, 0, 0],
+                DerError::NonMinimalLength,
+            ),
+            (&[0x04, 0x82, 0x00, 0x80], DerError::NonMinimalLength),
+            (&[0x04, 0x85, 1, 0, 0, 0, 0], DerError::LengthTooLarge),
+            (&[0x04, 0x82, 0x01], DerError::UnexpectedEnd),
+        ];
+
+        for (der, e) in cases {
+            assert_eq!(DerReader::new(der).read_element(0x04), Err(e));
+        }
+
+        assert_eq!(
+            DerReader::new(&[0x02, 0x00]).read_element(0x04),
+            Err(DerError::UnexpectedTag {
+                expected: 0x04,
+                found: 0x02
+            })
+        );
+        assert_eq!(
+            DerReader::new(&[0x04, 0x00, 0x00]).expect_end(),
+            Err(DerError::TrailingData)
+        );
+    }
+
+    #[test]
+    fn der_integers() {
+        for (magnitude, der) in [
+            (vec![], vec![0x02, 0x01, 0x00]),
+            (vec![0x00, 0x00], vec![0x02, 0x01, 0x00]),
+            (vec![0x7f], vec![0x02, 0x01, 0x7f]),
+            (vec![0x80], vec![0x02, 0x02, 0x00, 0x80]),
+            (vec![0x00, 0x01, 0x00], vec![0x02, 0x02, 0x01, 0x00]),
+        ] {
+            let mut w = DerWriter::new();
+            w.write_integer(&magnitude);
+            assert_eq!(w.finish(), der);
+
+            let mut r = DerReader::new(&der);
+            let read = r.read_integer().unwrap();
+            let skip = magnitude.iter().take_while(|b| **b == 0).count();
+            if magnitude[skip..].is_empty() {
+                assert_eq!(read, &[0]);
+            } else {
+                assert_eq!(read, &magnitude[skip..]);
+            }
+        }
+
+        for der in [
+            &[0x02, 0x00][..],
+            &[0x02, 0x01, 0x80],
+            &[0x02, 0x02, 0x00, 0x01],
+        ] {
+            assert_eq!(
+                DerReader::new(der).read_integer(),
+                Err(DerError::InvalidInteger)
+            );
+        }
+    }
+
+    #[test]
+    fn der_bit_strings_and_sequences() {
+        let mut w = DerWriter::new();
+        w.write_sequence(|w| {
+            w.write_oid(&[0x2b, 0x81, 0x04, 0x00, 0x0a]);
+            w.write_bit_string(0, &[0x04, 0x01]);
+        });
+        let der = w.finish();
+
+        let mut r = DerReader::new(&der);
+        let mut seq = r.read_sequence().unwrap();
+        r.expect_end().unwrap();
+        assert_eq!(seq.read_oid().unwrap(), &[0x2b, 0x81, 0x04, 0x00, 0x0a]);
+        assert_eq!(seq.read_bit_string().unwrap(), (0, &[0x04, 0x01][..]));
+        seq.expect_end().unwrap();
+
+        for der in [
+            &[0x03, 0x00][..],
+            &[0x03, 0x01, 0x01],
+            &[0x03, 0x02, 0x08, 0x00],
+        ] {
+            assert_eq!(
+                DerReader::new(der).read_bit_string(),
+                Err(DerError::InvalidBitString)
+            );
+        }
+    }
+
+    #[test]
+    fn der_reader_random_bytes() {
+        let mut rng = TestRng::new(219);
+        for _ in 0..10000 {
+            let len = (rng.next_u32() % 64) as usize;
+            let mut bytes = vec![0; len];
+            rng.fill_bytes(&mut bytes);
+            // Bias towards plausible tags so parsing gets past the first byte
+            if len > 0 && rng.next_u32() & 1 == 0 {
+                bytes[0] = [0x02, 0x03, 0x06, 0x30][(rng.next_u32() % 4) as usize];
+            }
+
+            let mut r = DerReader::new(&bytes);
+            if let Ok(mut seq) = r.read_sequence() {
+                let _ = seq.read_oid();
+                let _ = seq.read_integer();
+                let _ = seq.read_bit_string();
+                let _ = seq.expect_end();
+            }
+            let _ = DerReader::new(&bytes).read_integer();
+            let _ = DerReader::new(&bytes).read_bit_string();
+            let _ = DerReader::new(&bytes).read_oid();
+        }
+    }
+}
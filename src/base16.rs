@@ -4,6 +4,18 @@ pub fn decode_string(input: &str) -> Vec<u8> {
     return hex::decode(input).expect("decode-hex");
 }
 
+/// Decodes `input` into `out` without allocating. `out` must be exactly half
+/// as long as `input`.
+pub fn decode_into(input: &str, out: &mut [u8]) {
+    hex::decode_to_slice(input, out).expect("decode-hex");
+}
+
 pub fn encode_bytes(input: &[u8]) -> String {
     return hex::encode(input);
 }
+
+/// Writes the lowercase hex of `input` into `out` without allocating. `out`
+/// must be exactly twice as long as `input`.
+pub fn encode_bytes_into(input: &[u8], out: &mut [u8]) {
+    hex::encode_to_slice(input, out).expect("encode-hex");
+}
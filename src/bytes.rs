@@ -1,7 +1,44 @@
+/// The 256 bits of `i`, most significant first, one 0 or 1 per element.
 pub fn bytes_to_binary(i: &[u8; 32], r: &mut Vec<u8>) {
     for m in i.iter() {
-        format!("{:8b}", m)
-            .chars()
-            .for_each(|b| if b == '1' { r.push(1) } else { r.push(0) })
+        for shift in (0..8).rev() {
+            r.push((m >> shift) & 1);
+        }
+    }
+}
+
+/// Overwrites `buf` with zeros in a way the optimizer can't drop as a dead
+/// store, for buffers that held secret bits.
+pub fn wipe(buf: &mut [u8]) {
+    for b in buf.iter_mut() {
+        // SAFETY: `b` is a valid, aligned &mut u8
+        unsafe { std::ptr::write_volatile(b, 0) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::*;
+
+    #[test]
+    fn bytes_to_binary_msb_first() {
+        let mut i = [0; 32];
+        i[0] = 0b1000_0001;
+        i[31] = 0b0000_0110;
+
+        let mut r = vec![];
+        bytes_to_binary(&i, &mut r);
+        assert_eq!(r.len(), 256);
+        assert_eq!(r[..8], [1, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(r[248..], [0, 0, 0, 0, 0, 1, 1, 0]);
+        assert_eq!(r.iter().filter(|&&b| b == 1).count(), 4);
+    }
+
+    #[test]
+    fn wipe_zeroes() {
+        let mut buf = vec![0xa5; 40];
+        wipe(&mut buf);
+        assert!(buf.iter().all(|&b| b == 0));
     }
 }
@@ -0,0 +1,297 @@
+//! Minimal deterministic CBOR (RFC 8949), just the integers, byte strings
+//! and maps that COSE keys are made of. Decoding is strict: indefinite
+//! lengths, non-minimal arguments, duplicate map keys, large maps, other
+//! types and trailing data are errors, never panics. Encoding follows the core
+//! deterministic rules, so equal values always encode to the same bytes.
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_NINT: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_MAP: u8 = 5;
+
+/// Maps nested deeper than this are rejected rather than recursed into.
+const MAX_DEPTH: usize = 16;
+/// COSE keys have a handful of entries. The cap also bounds the duplicate
+/// key check, which compares each key against every earlier one.
+const MAX_MAP_ENTRIES: u64 = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CborValue {
+    /// Major types 0 and 1, so -2^64 to 2^64 - 1.
+    Int(i128),
+    Bytes(Vec<u8>),
+    /// Entries in the order they were decoded.
+    Map(Vec<(CborValue, CborValue)>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CborError {
+    UnexpectedEnd,
+    IndefiniteLength,
+    /// Additional information 28 to 30, unassigned.
+    Reserved,
+    /// An argument that fits a shorter encoding.
+    NonMinimal,
+    /// Text, arrays, tags, floats and simple values, by major type.
+    UnsupportedType(u8),
+    DuplicateKey,
+    /// Maps nested more than 16 deep.
+    TooDeep,
+    /// A map of more than 64 entries.
+    TooManyEntries,
+    TrailingData,
+    /// Encoding an `Int` outside -2^64 to 2^64 - 1.
+    IntOutOfRange,
+}
+
+impl CborValue {
+    /// The value under integer `key`, if this is a map that has it.
+    pub fn get(&self, key: i128) -> Option<&CborValue> {
+        let entries = match self {
+            CborValue::Map(entries) => entries,
+            _ => return None,
+        };
+        return entries
+            .iter()
+            .find(|(k, _)| *k == CborValue::Int(key))
+            .map(|(_, v)| v);
+    }
+}
+
+/// Exactly one item, with nothing after it.
+pub fn decode(input: &[u8]) -> Result<CborValue, CborError> {
+    let mut rest = input;
+    let value = read_value(&mut rest, 0)?;
+    if !rest.is_empty() {
+        return Err(CborError::TrailingData);
+    }
+    return Ok(value);
+}
+
+/// Deterministic encoding: shortest arguments, and map entries sorted by
+/// the bytes of their encoded keys.
+pub fn encode(value: &CborValue) -> Result<Vec<u8>, CborError> {
+    let mut out = vec![];
+    write_value(value, &mut out)?;
+    return Ok(out);
+}
+
+/// The major type and argument of the next item.
+fn read_head(input: &mut &[u8]) -> Result<(u8, u64), CborError> {
+    let (&first, rest) = input.split_first().ok_or(CborError::UnexpectedEnd)?;
+    let (major, info) = (first >> 5, first & 0x1f);
+
+    let (argument, rest) = match info {
+        0..=23 => (info as u64, rest),
+        24..=27 => {
+            let len = 1 << (info - 24);
+            if rest.len() < len {
+                return Err(CborError::UnexpectedEnd);
+            }
+
+            let (bytes, after) = rest.split_at(len);
+            let argument = bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            let shortest = [24, 0x100, 0x1_0000, 0x1_0000_0000][(info - 24) as usize];
+            if argument < shortest {
+                return Err(CborError::NonMinimal);
+            }
+            (argument, after)
+        }
+        31 => return Err(CborError::IndefiniteLength),
+        _ => return Err(CborError::Reserved),
+    };
+
+    *input = rest;
+    return Ok((major, argument));
+}
+
+fn read_value(input: &mut &[u8], depth: usize) -> Result<CborValue, CborError> {
+    let (major, argument) = read_head(input)?;
+    match major {
+        MAJOR_UINT => return Ok(CborValue::Int(argument as i128)),
+        MAJOR_NINT => return Ok(CborValue::Int(-1 - argument as i128)),
+        MAJOR_BYTES => {
+            if argument > input.len() as u64 {
+                return Err(CborError::UnexpectedEnd);
+            }
+            let (bytes, rest) = input.split_at(argument as usize);
+            *input = rest;
+            return Ok(CborValue::Bytes(bytes.to_vec()));
+        }
+        MAJOR_MAP => {
+            if depth == MAX_DEPTH {
+                return Err(CborError::TooDeep);
+            }
+            if argument > MAX_MAP_ENTRIES {
+                return Err(CborError::TooManyEntries);
+            }
+
+            // No preallocation from `argument`, every entry takes at least
+            // two bytes so a bogus count runs out of input quickly
+            let mut entries: Vec<(CborValue, CborValue)> = vec![];
+            for _ in 0..argument {
+                let key = read_value(input, depth + 1)?;
+                if entries.iter().any(|(k, _)| *k == key) {
+                    return Err(CborError::DuplicateKey);
+                }
+                let value = read_value(input, depth + 1)?;
+                entries.push((key, value));
+            }
+            return Ok(CborValue::Map(entries));
+        }
+        m => return Err(CborError::UnsupportedType(m)),
+    }
+}
+
+fn write_head(major: u8, argument: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend([major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(argument.to_be_bytes());
+        }
+    }
+}
+
+fn write_value(value: &CborValue, out: &mut Vec<u8>) -> Result<(), CborError> {
+    match value {
+        CborValue::Int(n) if *n >= 0 => {
+            let n = u64::try_from(*n).map_err(|_| CborError::IntOutOfRange)?;
+            write_head(MAJOR_UINT, n, out);
+        }
+        CborValue::Int(n) => {
+            let n = u64::try_from(-1 - *n).map_err(|_| CborError::IntOutOfRange)?;
+            write_head(MAJOR_NINT, n, out);
+        }
+        CborValue::Bytes(bytes) => {
+            write_head(MAJOR_BYTES, bytes.len() as u64, out);
+            out.extend(bytes);
+        }
+        CborValue::Map(entries) => {
+            let mut encoded: Vec<(Vec<u8>, Vec<u8>)> = entries
+                .iter()
+                .map(|(k, v)| Ok((encode(k)?, encode(v)?)))
+                .collect::<Result<_, CborError>>()?;
+            encoded.sort();
+
+            write_head(MAJOR_MAP, encoded.len() as u64, out);
+            for (k, v) in encoded {
+                out.extend(k);
+                out.extend(v);
+            }
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base16;
+    use crate::cbor::*;
+
+    #[test]
+    fn cbor_ints() {
+        // RFC 8949 appendix A
+        let vectors: [(i128, &str); 12] = [
+            (0, "00"),
+            (23, "17"),
+            (24, "1818"),
+            (100, "1864"),
+            (1000, "1903e8"),
+            (1000000, "1a000f4240"),
+            (1000000000000, "1b000000e8d4a51000"),
+            (18446744073709551615, "1bffffffffffffffff"),
+            (-1, "20"),
+            (-100, "3863"),
+            (-1000, "3903e7"),
+            (-18446744073709551616, "3bffffffffffffffff"),
+        ];
+        for (n, hex) in vectors {
+            let bytes = base16::decode_string(hex);
+            assert_eq!(encode(&CborValue::Int(n)), Ok(bytes.clone()), "{}", n);
+            assert_eq!(decode(&bytes), Ok(CborValue::Int(n)));
+        }
+    }
+
+    #[test]
+    fn cbor_bytes_and_maps() {
+        let value = CborValue::Map(vec![
+            (CborValue::Int(-1), CborValue::Bytes(vec![1, 2, 3, 4])),
+            (CborValue::Int(10), CborValue::Map(vec![])),
+            (CborValue::Int(1), CborValue::Bytes(vec![])),
+        ]);
+        // Keys sorted by their encoding: 01, 0a, 20
+        let bytes = base16::decode_string("a301400aa0204401020304");
+        assert_eq!(encode(&value), Ok(bytes.clone()));
+
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.get(-1), Some(&CborValue::Bytes(vec![1, 2, 3, 4])));
+        assert_eq!(decoded.get(10), Some(&CborValue::Map(vec![])));
+        assert_eq!(decoded.get(2), None);
+        assert_eq!(CborValue::Int(1).get(1), None);
+        assert_eq!(encode(&decoded), Ok(bytes));
+    }
+
+    #[test]
+    fn cbor_errors() {
+        let cases = [
+            ("", CborError::UnexpectedEnd),
+            ("18", CborError::UnexpectedEnd),
+            ("1817", CborError::NonMinimal),
+            ("1900ff", CborError::NonMinimal),
+            ("1b00000000ffffffff", CborError::NonMinimal),
+            ("1c", CborError::Reserved),
+            ("5f4101ff", CborError::IndefiniteLength),
+            ("bf0102ff", CborError::IndefiniteLength),
+            ("4301", CborError::UnexpectedEnd),
+            ("5bffffffffffffffff", CborError::UnexpectedEnd),
+            ("a201020103", CborError::DuplicateKey),
+            ("a2", CborError::UnexpectedEnd),
+            ("6161", CborError::UnsupportedType(3)),
+            ("8101", CborError::UnsupportedType(4)),
+            ("c240", CborError::UnsupportedType(6)),
+            ("f5", CborError::UnsupportedType(7)),
+            ("0000", CborError::TrailingData),
+        ];
+        for (hex, error) in cases {
+            assert_eq!(decode(&base16::decode_string(hex)), Err(error), "{}", hex);
+        }
+
+        // 16 nested maps are fine, 17 are not
+        let nested = |depth| {
+            let mut hex = "a100".repeat(depth);
+            hex.push_str("a0");
+            return base16::decode_string(&hex);
+        };
+        assert!(decode(&nested(15)).is_ok());
+        assert_eq!(decode(&nested(16)), Err(CborError::TooDeep));
+
+        // 64 entries are fine, a 65-entry header is rejected before reading
+        let mut map = vec![0xb8, 64];
+        for k in 0..64u8 {
+            map.extend(encode(&CborValue::Int(k as i128)).unwrap());
+            map.push(0x00);
+        }
+        assert!(decode(&map).is_ok());
+        assert_eq!(decode(&[0xb8, 65]), Err(CborError::TooManyEntries));
+        assert_eq!(
+            decode(&base16::decode_string("bbffffffffffffffff")),
+            Err(CborError::TooManyEntries)
+        );
+
+        let too_big = CborValue::Map(vec![(CborValue::Int(1 << 64), CborValue::Int(0))]);
+        assert_eq!(encode(&too_big), Err(CborError::IntOutOfRange));
+        let too_small = CborValue::Int(-(1 << 64) - 1);
+        assert_eq!(encode(&too_small), Err(CborError::IntOutOfRange));
+    }
+}
@@ -0,0 +1,235 @@
+//! Edge-case checks for secp256k1 group arithmetic, packaged so any
+//! implementation can be run against them. Implement `GroupOps` over
+//! `EccPoint` and pass it to `run`; the report lists every check that
+//! failed rather than stopping at the first.
+//!
+//! The identity is the crate's zero point (0, 0).
+
+use crate::secp256k1::{EccPoint, SECP256K1};
+use crate::u256::U256;
+
+/// The group operations under test.
+pub trait GroupOps {
+    fn add(&self, a: &EccPoint, b: &EccPoint) -> EccPoint;
+    fn double(&self, a: &EccPoint) -> EccPoint;
+    /// k·G for any k, including 0 and multiples of n.
+    fn mul_g(&self, k: &U256) -> EccPoint;
+}
+
+/// The crate's own affine arithmetic.
+impl GroupOps for SECP256K1 {
+    fn add(&self, a: &EccPoint, b: &EccPoint) -> EccPoint {
+        return SECP256K1::add_points(a, b);
+    }
+    fn double(&self, a: &EccPoint) -> EccPoint {
+        return SECP256K1::double_point(a);
+    }
+    fn mul_g(&self, k: &U256) -> EccPoint {
+        return SECP256K1::pr_to_pub(k);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceFailure {
+    pub check: String,
+    pub expected: EccPoint,
+    pub got: EccPoint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceReport {
+    /// Number of checks run, passed or not.
+    pub checks: usize,
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl ConformanceReport {
+    pub fn passed(&self) -> bool {
+        return self.failures.is_empty();
+    }
+
+    fn check(&mut self, name: &str, expected: &EccPoint, got: EccPoint) {
+        self.checks += 1;
+        if &got != expected {
+            self.failures.push(ConformanceFailure {
+                check: name.to_string(),
+                expected: *expected,
+                got,
+            });
+        }
+    }
+}
+
+/// Runs every check:
+///
+/// - identity laws: P + O = O + P = P, O + O = 2O = O
+/// - P + (-P) = O
+/// - commutativity on a few known points
+/// - known answers: G, 2G, 3G via add, double and mul_g
+/// - 2^i·G for i in 0..256 by repeated doubling, against `SECP256K1::g_pow2`
+/// - n·G = O and (n - 1)·G = -G
+pub fn run(ops: &impl GroupOps) -> ConformanceReport {
+    let mut report = ConformanceReport {
+        checks: 0,
+        failures: vec![],
+    };
+
+    let o = SECP256K1::zero_point();
+    let g = SECP256K1::g_pow2(0);
+    let g2 = SECP256K1::g_pow2(1);
+    let g3 = EccPoint::from_hex_coordinates(G3.0, G3.1).expect("g3");
+
+    for (name, pt) in [("G", &g), ("2G", &g2), ("3G", &g3)] {
+        report.check(&format!("{} + O", name), pt, ops.add(pt, &o));
+        report.check(&format!("O + {}", name), pt, ops.add(&o, pt));
+        report.check(
+            &format!("{} + -{}", name, name),
+            &o,
+            ops.add(pt, &negate(pt)),
+        );
+    }
+    report.check("O + O", &o, ops.add(&o, &o));
+    report.check("2O", &o, ops.double(&o));
+
+    for (name, a, b) in [("G, 2G", &g, &g2), ("G, 3G", &g, &g3), ("2G, 3G", &g2, &g3)] {
+        let ab = ops.add(a, b);
+        report.check(&format!("commutativity {}", name), &ab, ops.add(b, a));
+    }
+
+    report.check("G + G", &g2, ops.add(&g, &g));
+    report.check("double G", &g2, ops.double(&g));
+    report.check("G + 2G", &g3, ops.add(&g, &g2));
+    report.check("1·G", &g, ops.mul_g(&U256::one()));
+    report.check("2·G", &g2, ops.mul_g(&U256::new(2)));
+    report.check("3·G", &g3, ops.mul_g(&U256::new(3)));
+    report.check("0·G", &o, ops.mul_g(&U256::zero()));
+
+    let mut pt = g;
+    for i in 1..=255 {
+        pt = ops.double(&pt);
+        report.check(&format!("2^{}·G", i), &SECP256K1::g_pow2(i), pt);
+    }
+
+    let n = SECP256K1::n();
+    let n_minus_1 = n.checked_sub(&U256::ONE).expect("n-minus-1");
+    report.check("n·G", &o, ops.mul_g(&n));
+    report.check("(n - 1)·G", &negate(&g), ops.mul_g(&n_minus_1));
+
+    return report;
+}
+
+/// -P = (x, p - y), and -O = O.
+fn negate(pt: &EccPoint) -> EccPoint {
+    if pt.is_zero_point() {
+        return *pt;
+    }
+    return EccPoint {
+        x: pt.x,
+        y: pt.y.neg_mod(&SECP256K1::p()),
+    };
+}
+
+const G3: (&str, &str) = (
+    "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+    "388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672",
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::conformance::*;
+    use ::secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+    /// The C library, as an independent implementation to check the suite
+    /// itself against. It has no encoding for the identity, so O is mapped
+    /// to the zero point at the boundary.
+    struct Libsecp256k1;
+
+    fn to_pub(pt: &EccPoint) -> Option<PublicKey> {
+        if pt.is_zero_point() {
+            return None;
+        }
+        let mut sec1 = [4; 65];
+        sec1[1..33].copy_from_slice(&pt.x.to_be_bytes());
+        sec1[33..].copy_from_slice(&pt.y.to_be_bytes());
+        return Some(PublicKey::from_slice(&sec1).expect("on-curve"));
+    }
+
+    fn from_pub(pk: Option<PublicKey>) -> EccPoint {
+        match pk {
+            Some(pk) => {
+                let sec1 = pk.serialize_uncompressed();
+                return EccPoint {
+                    x: U256::from_be_bytes(&sec1[1..33]),
+                    y: U256::from_be_bytes(&sec1[33..]),
+                };
+            }
+            None => return SECP256K1::zero_point(),
+        }
+    }
+
+    impl GroupOps for Libsecp256k1 {
+        fn add(&self, a: &EccPoint, b: &EccPoint) -> EccPoint {
+            match (to_pub(a), to_pub(b)) {
+                (Some(a), Some(b)) => return from_pub(a.combine(&b).ok()),
+                (None, _) => return *b,
+                (_, None) => return *a,
+            }
+        }
+        fn double(&self, a: &EccPoint) -> EccPoint {
+            return self.add(a, a);
+        }
+        fn mul_g(&self, k: &U256) -> EccPoint {
+            let n = SECP256K1::n();
+            let bytes = k.div_rem(&n).1.to_be_bytes();
+
+            // Fails only for k = 0 (mod n)
+            return from_pub(
+                SecretKey::from_slice(&bytes)
+                    .ok()
+                    .map(|sk| PublicKey::from_secret_key(&Secp256k1::new(), &sk)),
+            );
+        }
+    }
+
+    /// Doubling that forgets to negate y, a plausible slip.
+    struct BrokenDouble;
+
+    impl GroupOps for BrokenDouble {
+        fn add(&self, a: &EccPoint, b: &EccPoint) -> EccPoint {
+            return Libsecp256k1.add(a, b);
+        }
+        fn double(&self, a: &EccPoint) -> EccPoint {
+            let mut pt = Libsecp256k1.double(a);
+            pt.y = pt.y.neg_mod(&SECP256K1::p());
+            return pt;
+        }
+        fn mul_g(&self, k: &U256) -> EccPoint {
+            return Libsecp256k1.mul_g(k);
+        }
+    }
+
+    #[test]
+    fn conformance_libsecp256k1() {
+        let report = run(&Libsecp256k1);
+        assert!(report.passed(), "{:?}", report.failures);
+        assert_eq!(report.checks, 278);
+    }
+
+    #[test]
+    fn conformance_reports_failures() {
+        let report = run(&BrokenDouble);
+        assert_eq!(report.checks, 278);
+        // 2O = O still holds. Along the doubling chain the sign error cancels
+        // out every second step, so only odd powers fail.
+        assert_eq!(report.failures.len(), 1 + 128);
+        assert_eq!(report.failures[0].check, "double G");
+        assert_eq!(report.failures[1].check, "2^1·G");
+        assert_eq!(report.failures[2].check, "2^3·G");
+    }
+
+    #[test]
+    fn conformance_secp256k1() {
+        let report = run(&SECP256K1);
+        assert!(report.passed(), "{:?}", report.failures);
+    }
+}
@@ -10,58 +10,1001 @@ pub mod hashing {
         return r;
     }
 
-    pub fn hash_keccak256_str(input: &String) -> String {
+    pub fn hash_keccak256_str(input: &str) -> String {
         let mut hasher = Keccak256::default();
-        hasher.update(input.clone().into_bytes());
+        hasher.update(input.as_bytes());
         let out = hasher.finalize();
         let r = base16::encode_bytes(&out).to_uppercase();
         return r;
     }
+
+    pub fn hash_keccak256_bytes(input: &[u8]) -> [u8; 32] {
+        let mut hasher = Keccak256::default();
+        hasher.update(input);
+        return hasher.finalize().into();
+    }
+
+    /// Lowercase hex of a 32-byte digest, written into `out` without
+    /// allocating.
+    pub fn write_digest_hex(digest: &[u8; 32], out: &mut [u8; 64]) {
+        base16::encode_bytes_into(digest, out);
+    }
 }
 
 pub mod ethereum {
     use crate::base16;
-    use crate::crypto::hashing::{hash_keccak256, hash_keccak256_str};
-    use std::u8;
+    use crate::crypto::hashing::hash_keccak256_bytes;
+    use crate::u256::U256;
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum AddressError {
+        MissingPrefix,
+        InvalidLength(usize),
+        InvalidCharacter(char),
+        /// Mixed case that isn't the EIP-55 checksum.
+        InvalidChecksum,
+        /// From `address_from_any`, for input that looked like an ICAP.
+        Icap(IcapError),
+    }
+
+    /// An `AddressError` from `normalize_addresses`, with the 0-based index
+    /// of the input it came from.
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct AddressRowError {
+        pub row: usize,
+        pub error: AddressError,
+    }
 
-    pub fn derive_address(pub_key: &str) -> String {
-        let pub_key_x = String::from(&pub_key[2..66]).to_uppercase();
-        let pub_key_y = String::from(&pub_key[66..130]).to_uppercase();
+    /// A malformed hex public key for `derive_address`.
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum PubKeyError {
+        /// Not the 130 characters of "04" || x || y.
+        InvalidLength(usize),
+        /// Not starting with "04", the uncompressed SEC1 prefix.
+        NotUncompressed,
+        InvalidCharacter(char),
+    }
 
-        let origin = format!("{}{}", pub_key_x, pub_key_y);
-        let uncompressed_pub_hash = hash_keccak256(&base16::decode_string(&origin));
+    /// `pub_key` is the uncompressed key in hex, "04" || x || y. The address
+    /// is the last 20 bytes of keccak256(x || y).
+    pub fn derive_address(pub_key: &str) -> Result<String, PubKeyError> {
+        if pub_key.len() != 130 {
+            return Err(PubKeyError::InvalidLength(pub_key.len()));
+        }
+        if let Some(c) = pub_key.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(PubKeyError::InvalidCharacter(c));
+        }
+        if !pub_key.starts_with("04") {
+            return Err(PubKeyError::NotUncompressed);
+        }
 
-        let non_check_summed_address = format!("0x{}", &uncompressed_pub_hash[24..64]).to_lowercase();
+        let mut xy = [0; 64];
+        base16::decode_into(&pub_key[2..], &mut xy);
 
-        let address = check_sum(&non_check_summed_address);
+        let mut address = [0; 20];
+        address.copy_from_slice(&hash_keccak256_bytes(&xy)[12..]);
 
-        return address;
+        let mut out = [0; 42];
+        write_checksummed(&address, &mut out);
+        return Ok(String::from_utf8(out.to_vec()).expect("ascii"));
     }
 
     /// Compare non-checksummed address with the first 40 characters of the hash
-    /// of the non-checksummed address. If the hex nibble is greater than 8,
-    /// capitalize it (only applies to numbers), otherwise lowercase it.
-    pub fn check_sum(address: &str) -> String {
-        assert!(address.len() == 42);
-
-        let ad = String::from(&address[2..]).to_lowercase();
-        let h = hash_keccak256_str(&ad);
-
-        let r: String = ad
-            .chars()
-            .zip(h.chars())
-            .map(|(c, flag)| {
-                if c.is_alphabetic()
-                    && u8::from_str_radix(flag.to_string().as_str(), 16).unwrap() > 8
-                {
-                    c.to_ascii_uppercase()
+    /// of the non-checksummed address. If the hex nibble is 8 or greater,
+    /// capitalize it (only applies to letters), otherwise lowercase it. The
+    /// input's own casing is ignored.
+    pub fn check_sum(address: &str) -> Result<String, AddressError> {
+        let bytes = parse_address(address)?;
+
+        let mut out = [0; 42];
+        write_checksummed(&bytes, &mut out);
+        return Ok(String::from_utf8(out.to_vec()).expect("ascii"));
+    }
+
+    /// Allocation-free `check_sum`: writes the 0x-prefixed checksummed form of
+    /// the 20 address bytes into `out`.
+    pub fn write_checksummed(address: &[u8; 20], out: &mut [u8; 42]) {
+        out[0] = b'0';
+        out[1] = b'x';
+        base16::encode_bytes_into(address, &mut out[2..]);
+
+        let h = hash_keccak256_bytes(&out[2..]);
+        apply_check_sum(&mut out[2..], &h);
+    }
+
+    /// How alike two addresses look in hex, compared on the raw bytes so
+    /// checksum casing doesn't matter.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct AddressSimilarity {
+        /// Leading hex digits the two have in common.
+        pub prefix_nibbles: usize,
+        /// Trailing hex digits the two have in common.
+        pub suffix_nibbles: usize,
+        /// Number of the 40 hex digits that differ.
+        pub hamming_distance: usize,
+    }
+
+    pub fn address_similarity(a: &[u8; 20], b: &[u8; 20]) -> AddressSimilarity {
+        let nibbles = |ad: &[u8; 20]| -> Vec<u8> {
+            return ad.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect();
+        };
+        let (na, nb) = (nibbles(a), nibbles(b));
+        let same: Vec<bool> = na.iter().zip(nb.iter()).map(|(x, y)| x == y).collect();
+
+        return AddressSimilarity {
+            prefix_nibbles: same.iter().take_while(|s| **s).count(),
+            suffix_nibbles: same.iter().rev().take_while(|s| **s).count(),
+            hamming_distance: same.iter().filter(|s| !**s).count(),
+        };
+    }
+
+    /// Address poisoning check: true if the two addresses are different but
+    /// share at least `prefix_n` leading and `suffix_n` trailing hex digits,
+    /// which is all most wallets show.
+    pub fn is_suspicious_lookalike(
+        a: &[u8; 20],
+        b: &[u8; 20],
+        prefix_n: usize,
+        suffix_n: usize,
+    ) -> bool {
+        let sim = address_similarity(a, b);
+        return sim.hamming_distance > 0
+            && sim.prefix_nibbles >= prefix_n
+            && sim.suffix_nibbles >= suffix_n;
+    }
+
+    /// EIP-1191 checksum. Same as `check_sum`, except the hashed preimage is
+    /// `chainId + "0x" + address` (chain id in decimal, address lowercase)
+    /// when a chain id is given. With `None` this is plain EIP-55.
+    pub fn check_sum_with_chain_id(
+        address: &str,
+        chain_id: Option<u64>,
+    ) -> Result<String, AddressError> {
+        let bytes = parse_address(address)?;
+
+        let mut out = [0; 42];
+        out[0] = b'0';
+        out[1] = b'x';
+        base16::encode_bytes_into(&bytes, &mut out[2..]);
+
+        let h = match chain_id {
+            Some(id) => {
+                let mut preimage = id.to_string().into_bytes();
+                preimage.extend(&out);
+                hash_keccak256_bytes(&preimage)
+            }
+            None => hash_keccak256_bytes(&out[2..]),
+        };
+        apply_check_sum(&mut out[2..], &h);
+
+        return Ok(String::from_utf8(out.to_vec()).expect("ascii"));
+    }
+
+    /// True if `address` is exactly the EIP-1191 (or EIP-55 for `None`)
+    /// checksummed form of itself.
+    pub fn is_valid_checksum_with_chain_id(address: &str, chain_id: Option<u64>) -> bool {
+        match check_sum_with_chain_id(address, chain_id) {
+            Ok(r) => return r == address,
+            Err(_) => return false,
+        }
+    }
+
+    /// Parses a batch of addresses and re-emits each in EIP-55 form. All
+    /// lowercase or all uppercase input is accepted as is, mixed case must
+    /// already be correctly checksummed. One output buffer is reused for the
+    /// whole batch.
+    pub fn normalize_addresses<'a>(
+        input: impl Iterator<Item = &'a str>,
+    ) -> Vec<Result<String, AddressRowError>> {
+        let mut out = [0; 42];
+
+        return input
+            .enumerate()
+            .map(|(row, address)| {
+                let bytes = parse_checked_address(address)
+                    .map_err(|error| AddressRowError { row, error })?;
+                write_checksummed(&bytes, &mut out);
+
+                return Ok(std::str::from_utf8(&out).expect("ascii").to_string());
+            })
+            .collect();
+    }
+
+    /// `parse_address`, but mixed case must be the EIP-55 checksum.
+    fn parse_checked_address(address: &str) -> Result<[u8; 20], AddressError> {
+        let bytes = parse_address(address)?;
+
+        let hex = &address[2..];
+        let mixed = hex.chars().any(|c| c.is_ascii_lowercase())
+            && hex.chars().any(|c| c.is_ascii_uppercase());
+        if mixed {
+            let mut out = [0; 42];
+            write_checksummed(&bytes, &mut out);
+            if hex.as_bytes() != &out[2..] {
+                return Err(AddressError::InvalidChecksum);
+            }
+        }
+
+        return Ok(bytes);
+    }
+
+    /// Strips the 0x prefix and decodes the 40 hex characters, in either case.
+    fn parse_address(address: &str) -> Result<[u8; 20], AddressError> {
+        let ad = match address.strip_prefix("0x") {
+            Some(ad) => ad,
+            None => return Err(AddressError::MissingPrefix),
+        };
+        if ad.len() != 40 {
+            return Err(AddressError::InvalidLength(address.len()));
+        }
+        if let Some(c) = ad.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(AddressError::InvalidCharacter(c));
+        }
+
+        let mut bytes = [0; 20];
+        base16::decode_into(ad, &mut bytes);
+        return Ok(bytes);
+    }
+
+    /// Uppercases each letter of the lowercase hex `ad` whose nibble in the
+    /// digest `h` is 8 or greater.
+    fn apply_check_sum(ad: &mut [u8], h: &[u8; 32]) {
+        for (i, c) in ad.iter_mut().enumerate() {
+            let flag = if i % 2 == 0 {
+                h[i / 2] >> 4
+            } else {
+                h[i / 2] & 0x0f
+            };
+            if c.is_ascii_alphabetic() && flag >= 8 {
+                c.make_ascii_uppercase();
+            }
+        }
+    }
+
+    /*
+     * ICAP
+     */
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum IcapError {
+        /// Not starting with the "XE" country code.
+        InvalidPrefix,
+        /// Only direct (30) and basic (31 character) account numbers are
+        /// supported, this is the full ICAP length.
+        InvalidLength(usize),
+        InvalidCharacter(char),
+        /// The IBAN mod-97 check digits don't match.
+        InvalidChecksum,
+        /// The account number decodes to 2^160 or more.
+        Overflow,
+    }
+
+    const BASE36: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    /// "XE", two check digits, then the address in base 36. Direct ICAP (30
+    /// digits, zero-padded) when the address is below 36^30, about 2^155,
+    /// otherwise basic ICAP (31 digits), as geth does.
+    pub fn address_to_icap(address: &[u8; 20]) -> String {
+        let mut n = U256::from_be_bytes(address);
+        let mut digits = [b'0'; 31];
+        for c in digits.iter_mut().rev() {
+            let (q, r) = n.div_rem(&U256::new(36));
+            *c = BASE36[r.as_limbs()[0] as usize];
+            n = q;
+        }
+
+        // 36^31 > 2^160, so the leading digit is the only optional one
+        let bban = match digits[0] {
+            b'0' => &digits[1..],
+            _ => &digits[..],
+        };
+        let bban = std::str::from_utf8(bban).expect("ascii");
+        return format!("XE{:02}{}", 98 - icap_mod97(bban, "00"), bban);
+    }
+
+    /// Parses a direct or basic ICAP, in either case.
+    pub fn address_from_icap(icap: &str) -> Result<[u8; 20], IcapError> {
+        let icap = icap.to_ascii_uppercase();
+        if !icap.starts_with("XE") {
+            return Err(IcapError::InvalidPrefix);
+        }
+        if icap.len() != 34 && icap.len() != 35 {
+            return Err(IcapError::InvalidLength(icap.len()));
+        }
+        if let Some(c) = icap.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(IcapError::InvalidCharacter(c));
+        }
+
+        let (check, bban) = icap[2..].split_at(2);
+        if !check.bytes().all(|c| c.is_ascii_digit()) || icap_mod97(bban, check) != 1 {
+            return Err(IcapError::InvalidChecksum);
+        }
+
+        // At most 36^31 < 2^161, no overflow in 256 bits
+        let mut n = U256::zero();
+        for c in bban.bytes() {
+            let d = BASE36.iter().position(|&b| b == c).expect("base36");
+            n = n
+                .checked_mul(&U256::new(36))
+                .and_then(|n| n.checked_add(&U256::new(d as u64)))
+                .expect("base36-fits");
+        }
+        if n.bit_len() > 160 {
+            return Err(IcapError::Overflow);
+        }
+
+        let mut address = [0; 20];
+        address.copy_from_slice(&n.to_be_bytes()[12..]);
+        return Ok(address);
+    }
+
+    /// A hex address (lowercase, uppercase or EIP-55) or an ICAP, with
+    /// surrounding whitespace ignored.
+    pub fn address_from_any(address: &str) -> Result<[u8; 20], AddressError> {
+        let address = address.trim();
+        if address
+            .get(..2)
+            .is_some_and(|p| p.eq_ignore_ascii_case("XE"))
+        {
+            return address_from_icap(address).map_err(AddressError::Icap);
+        }
+        return parse_checked_address(address);
+    }
+
+    /// bban || "XE" || check mod 97, each letter read as the two digits
+    /// 10..35 as in IBAN.
+    fn icap_mod97(bban: &str, check: &str) -> u32 {
+        return bban
+            .bytes()
+            .chain(*b"XE")
+            .chain(check.bytes())
+            .fold(0, |r, c| {
+                let d = BASE36.iter().position(|&b| b == c).expect("base36") as u32;
+                return if d < 10 {
+                    (r * 10 + d) % 97
                 } else {
-                    c
+                    (r * 100 + d) % 97
+                };
+            });
+    }
+
+    /*
+     * ENS
+     */
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum EnsError {
+        EmptyLabel,
+        /// DNS labels are at most 63 bytes.
+        LabelTooLong(usize),
+        /// The encoded name is over DNS's 255-byte limit.
+        NameTooLong(usize),
+    }
+
+    /// ENS namehash (EIP-137):
+    ///
+    /// namehash("") = 0^32
+    /// namehash(label.rest) = keccak256(namehash(rest) || keccak256(label))
+    ///
+    /// `name` must already be normalized (UTS-46), this does no case folding.
+    pub fn namehash(name: &str) -> [u8; 32] {
+        let mut node = [0; 32];
+        if name.is_empty() {
+            return node;
+        }
+
+        for label in name.rsplit('.') {
+            let mut preimage = [0; 64];
+            preimage[..32].copy_from_slice(&node);
+            preimage[32..].copy_from_slice(&hash_keccak256_bytes(label.as_bytes()));
+            node = hash_keccak256_bytes(&preimage);
+        }
+
+        return node;
+    }
+
+    /// Node of the reverse record `<address lowercase hex>.addr.reverse`.
+    pub fn reverse_node(address: &[u8; 20]) -> [u8; 32] {
+        return namehash(&format!("{}.addr.reverse", base16::encode_bytes(address)));
+    }
+
+    /// DNS wire format, as ENSIP-10 resolvers take it: each label prefixed by
+    /// its length, then a zero byte. "" encodes to the root, [0].
+    pub fn dns_encode_name(name: &str) -> Result<Vec<u8>, EnsError> {
+        let mut r = vec![];
+        if !name.is_empty() {
+            for label in name.split('.') {
+                if label.is_empty() {
+                    return Err(EnsError::EmptyLabel);
                 }
-            })
+                if label.len() > 63 {
+                    return Err(EnsError::LabelTooLong(label.len()));
+                }
+                r.push(label.len() as u8);
+                r.extend(label.as_bytes());
+            }
+        }
+        r.push(0);
+
+        if r.len() > 255 {
+            return Err(EnsError::NameTooLong(r.len()));
+        }
+        return Ok(r);
+    }
+
+    /*
+     * ABI ENCODING
+     */
+
+    /// A value to ABI-encode. Which Solidity type it is encoded as comes from
+    /// the function signature, so one variant can serve several types (`Uint`
+    /// covers every `uintN`, `Array` covers both `T[]` and `T[k]`).
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum AbiValue {
+        Address([u8; 20]),
+        Bool(bool),
+        Uint(U256),
+        /// Sign-extended to 256 bits, so only `intN` values that fit in an
+        /// i128 can be expressed.
+        Int(i128),
+        /// `bytesN`, the length must equal N.
+        FixedBytes(Vec<u8>),
+        Bytes(Vec<u8>),
+        String(String),
+        Array(Vec<AbiValue>),
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum AbiError {
+        InvalidSignature(String),
+        UnsupportedType(String),
+        ArgumentCount {
+            expected: usize,
+            got: usize,
+        },
+        /// `index` is the position of the top-level argument the bad value
+        /// belongs to.
+        TypeMismatch {
+            index: usize,
+            expected: String,
+        },
+        OutOfRange {
+            index: usize,
+            expected: String,
+        },
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum AbiType {
+        Address,
+        Bool,
+        Uint(usize),
+        Int(usize),
+        FixedBytes(usize),
+        Bytes,
+        String,
+        Array(Box<AbiType>),
+        FixedArray(Box<AbiType>, usize),
+    }
+
+    impl AbiType {
+        fn parse(s: &str) -> Result<Self, AbiError> {
+            let unsupported = || AbiError::UnsupportedType(String::from(s));
+
+            if let Some(inner) = s.strip_suffix(']') {
+                let open = inner.rfind('[').ok_or_else(unsupported)?;
+                let elem = Self::parse(&inner[..open])?;
+                // Only one level of arrays is supported
+                if let Self::Array(_) | Self::FixedArray(_, _) = elem {
+                    return Err(unsupported());
+                }
+                let len = &inner[open + 1..];
+                if len.is_empty() {
+                    return Ok(Self::Array(Box::new(elem)));
+                }
+                let k = len.parse::<usize>().map_err(|_| unsupported())?;
+                return Ok(Self::FixedArray(Box::new(elem), k));
+            }
+
+            let size = |prefix: &str, max: usize, step: usize| {
+                let digits = &s[prefix.len()..];
+                if digits.starts_with('0') {
+                    return Err(unsupported());
+                }
+                match digits.parse::<usize>() {
+                    Ok(n) if n > 0 && n <= max && n % step == 0 => Ok(n),
+                    _ => Err(unsupported()),
+                }
+            };
+
+            return match s {
+                "address" => Ok(Self::Address),
+                "bool" => Ok(Self::Bool),
+                "bytes" => Ok(Self::Bytes),
+                "string" => Ok(Self::String),
+                "uint" => Ok(Self::Uint(256)),
+                "int" => Ok(Self::Int(256)),
+                _ if s.starts_with("uint") => Ok(Self::Uint(size("uint", 256, 8)?)),
+                _ if s.starts_with("int") => Ok(Self::Int(size("int", 256, 8)?)),
+                _ if s.starts_with("bytes") => Ok(Self::FixedBytes(size("bytes", 32, 1)?)),
+                _ => Err(unsupported()),
+            };
+        }
+
+        /// Canonical name, as used in the selector preimage.
+        fn name(&self) -> String {
+            return match self {
+                Self::Address => String::from("address"),
+                Self::Bool => String::from("bool"),
+                Self::Uint(n) => format!("uint{}", n),
+                Self::Int(n) => format!("int{}", n),
+                Self::FixedBytes(n) => format!("bytes{}", n),
+                Self::Bytes => String::from("bytes"),
+                Self::String => String::from("string"),
+                Self::Array(t) => format!("{}[]", t.name()),
+                Self::FixedArray(t, k) => format!("{}[{}]", t.name(), k),
+            };
+        }
+
+        fn is_dynamic(&self) -> bool {
+            return match self {
+                Self::Bytes | Self::String | Self::Array(_) => true,
+                Self::FixedArray(t, _) => t.is_dynamic(),
+                _ => false,
+            };
+        }
+
+        /// Number of bytes the type takes up in the head of its enclosing
+        /// tuple. None if that overflows, for a huge fixed array.
+        fn head_size(&self) -> Option<usize> {
+            return match self {
+                Self::FixedArray(t, k) if !self.is_dynamic() => t.head_size()?.checked_mul(*k),
+                _ => Some(32),
+            };
+        }
+    }
+
+    /// Splits `name(type1,type2,...)` into the name and the parsed argument
+    /// types. Whitespace is ignored and tuple types are not supported.
+    fn parse_signature(signature: &str) -> Result<(String, Vec<AbiType>), AbiError> {
+        let invalid = || AbiError::InvalidSignature(String::from(signature));
+
+        let sig: String = signature.chars().filter(|c| !c.is_whitespace()).collect();
+        let open = sig.find('(').ok_or_else(invalid)?;
+        let params = sig[open + 1..].strip_suffix(')').ok_or_else(invalid)?;
+        let name = &sig[..open];
+
+        if name.is_empty() || params.contains('(') || params.contains(')') {
+            return Err(invalid());
+        }
+
+        return Ok((String::from(name), parse_types(params)?));
+    }
+
+    fn parse_types(params: &str) -> Result<Vec<AbiType>, AbiError> {
+        if params.is_empty() {
+            return Ok(vec![]);
+        }
+
+        return params
+            .split(',')
+            .map(|t| AbiType::parse(t.trim()))
+            .collect::<Result<Vec<AbiType>, AbiError>>();
+    }
+
+    /// First four bytes of the keccak256 hash of the canonical signature
+    /// (`uint` is normalized to `uint256` etc. before hashing).
+    pub fn function_selector(function_signature: &str) -> Result<[u8; 4], AbiError> {
+        let (name, types) = parse_signature(function_signature)?;
+        let names: Vec<String> = types.iter().map(|t| t.name()).collect();
+        let canonical = format!("{}({})", name, names.join(","));
+
+        let h = hash_keccak256_bytes(canonical.as_bytes());
+        return Ok([h[0], h[1], h[2], h[3]]);
+    }
+
+    /// Builds call data: the 4-byte function selector followed by the
+    /// standard (head/tail) ABI encoding of `args`. Arguments are checked
+    /// against the types in `function_signature`.
+    ///
+    /// For `transfer(address,uint256)` this gives
+    /// `a9059cbb || pad32(to) || pad32(amount)`.
+    pub fn abi_encode(function_signature: &str, args: &[AbiValue]) -> Result<Vec<u8>, AbiError> {
+        let (_, types) = parse_signature(function_signature)?;
+
+        let mut r = function_selector(function_signature)?.to_vec();
+        r.extend(encode_args(&types, args)?);
+
+        return Ok(r);
+    }
+
+    /// Solidity's `abi.encode`: the argument encoding without a selector.
+    /// `types` is a comma-separated list, e.g. `"bytes32,address,uint256"`.
+    pub fn abi_encode_params(types: &str, args: &[AbiValue]) -> Result<Vec<u8>, AbiError> {
+        return encode_args(&parse_types(types)?, args);
+    }
+
+    fn encode_args(types: &[AbiType], args: &[AbiValue]) -> Result<Vec<u8>, AbiError> {
+        if types.len() != args.len() {
+            return Err(AbiError::ArgumentCount {
+                expected: types.len(),
+                got: args.len(),
+            });
+        }
+
+        let items: Vec<(&AbiType, &AbiValue, usize)> = types
+            .iter()
+            .zip(args.iter())
+            .enumerate()
+            .map(|(i, (t, v))| (t, v, i))
             .collect();
 
-        return format!("0x{}", r);
+        return encode_tuple(&items);
+    }
+
+    /// Static values are written in place; dynamic values get a 32-byte
+    /// offset (relative to the start of the tuple) in the head and their
+    /// contents appended after all the heads.
+    fn encode_tuple(items: &[(&AbiType, &AbiValue, usize)]) -> Result<Vec<u8>, AbiError> {
+        let mut head_len: usize = 0;
+        for (t, _, _) in items {
+            head_len = t
+                .head_size()
+                .and_then(|n| head_len.checked_add(n))
+                .ok_or_else(|| AbiError::UnsupportedType(t.name()))?;
+        }
+
+        let mut head = vec![];
+        let mut tail = vec![];
+        for (t, v, index) in items {
+            let enc = encode_value(t, v, *index)?;
+            if t.is_dynamic() {
+                head.extend(word_from_usize(head_len + tail.len()));
+                tail.extend(enc);
+            } else {
+                head.extend(enc);
+            }
+        }
+
+        head.extend(tail);
+        return Ok(head);
+    }
+
+    fn encode_value(t: &AbiType, v: &AbiValue, index: usize) -> Result<Vec<u8>, AbiError> {
+        let mismatch = || AbiError::TypeMismatch {
+            index,
+            expected: t.name(),
+        };
+        let out_of_range = || AbiError::OutOfRange {
+            index,
+            expected: t.name(),
+        };
+
+        match (t, v) {
+            (AbiType::Address, AbiValue::Address(a)) => {
+                let mut w = [0; 32];
+                w[12..].copy_from_slice(a);
+                return Ok(w.to_vec());
+            }
+            (AbiType::Bool, AbiValue::Bool(b)) => {
+                let mut w = [0; 32];
+                w[31] = *b as u8;
+                return Ok(w.to_vec());
+            }
+            (AbiType::Uint(n), AbiValue::Uint(u)) => {
+                if u.bit_len() > *n {
+                    return Err(out_of_range());
+                }
+                return Ok(u.to_be_bytes().to_vec());
+            }
+            (AbiType::Int(n), AbiValue::Int(i)) => {
+                if *n < 128 && (*i < -(1i128 << (n - 1)) || *i >= 1i128 << (n - 1)) {
+                    return Err(out_of_range());
+                }
+                let fill = if *i < 0 { 0xff } else { 0 };
+                let mut w = [fill; 32];
+                w[16..].copy_from_slice(&i.to_be_bytes());
+                return Ok(w.to_vec());
+            }
+            (AbiType::FixedBytes(n), AbiValue::FixedBytes(b)) => {
+                if b.len() != *n {
+                    return Err(mismatch());
+                }
+                let mut w = [0; 32];
+                w[..*n].copy_from_slice(b);
+                return Ok(w.to_vec());
+            }
+            (AbiType::Bytes, AbiValue::Bytes(b)) => return Ok(encode_dynamic_bytes(b)),
+            (AbiType::String, AbiValue::String(s)) => {
+                return Ok(encode_dynamic_bytes(s.as_bytes()))
+            }
+            (AbiType::Array(elem), AbiValue::Array(vs)) => {
+                let items: Vec<(&AbiType, &AbiValue, usize)> =
+                    vs.iter().map(|v| (elem.as_ref(), v, index)).collect();
+                let mut r = word_from_usize(vs.len());
+                r.extend(encode_tuple(&items)?);
+                return Ok(r);
+            }
+            (AbiType::FixedArray(elem, k), AbiValue::Array(vs)) => {
+                if vs.len() != *k {
+                    return Err(mismatch());
+                }
+                let items: Vec<(&AbiType, &AbiValue, usize)> =
+                    vs.iter().map(|v| (elem.as_ref(), v, index)).collect();
+                return encode_tuple(&items);
+            }
+            _ => return Err(mismatch()),
+        }
+    }
+
+    /// Length word followed by the data right-padded to a multiple of 32.
+    fn encode_dynamic_bytes(b: &[u8]) -> Vec<u8> {
+        let mut r = word_from_usize(b.len());
+        r.extend(b);
+        r.resize(r.len() + (32 - b.len() % 32) % 32, 0);
+        return r;
+    }
+
+    fn word_from_usize(n: usize) -> Vec<u8> {
+        let mut w = [0; 32];
+        w[24..].copy_from_slice(&(n as u64).to_be_bytes());
+        return w.to_vec();
+    }
+
+    pub mod erc4337 {
+        use crate::crypto::ethereum::{abi_encode_params, AbiValue};
+        use crate::crypto::hashing::hash_keccak256_bytes;
+        use crate::u256::U256;
+
+        /// ERC-4337 user operation, EntryPoint v0.6 layout.
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct UserOperation {
+            pub sender: [u8; 20],
+            pub nonce: U256,
+            pub init_code: Vec<u8>,
+            pub call_data: Vec<u8>,
+            pub call_gas_limit: U256,
+            pub verification_gas_limit: U256,
+            pub pre_verification_gas: U256,
+            pub max_fee_per_gas: U256,
+            pub max_priority_fee_per_gas: U256,
+            pub paymaster_and_data: Vec<u8>,
+            /// Not part of the hash.
+            pub signature: Vec<u8>,
+        }
+
+        impl UserOperation {
+            /// `UserOperationLib.pack` from EntryPoint v0.6: every field but
+            /// the signature, with the dynamic fields replaced by their
+            /// keccak256 hashes, ABI-encoded.
+            pub fn pack(&self) -> Vec<u8> {
+                let args = [
+                    AbiValue::Address(self.sender),
+                    AbiValue::Uint(self.nonce),
+                    AbiValue::FixedBytes(hash_keccak256_bytes(&self.init_code).to_vec()),
+                    AbiValue::FixedBytes(hash_keccak256_bytes(&self.call_data).to_vec()),
+                    AbiValue::Uint(self.call_gas_limit),
+                    AbiValue::Uint(self.verification_gas_limit),
+                    AbiValue::Uint(self.pre_verification_gas),
+                    AbiValue::Uint(self.max_fee_per_gas),
+                    AbiValue::Uint(self.max_priority_fee_per_gas),
+                    AbiValue::FixedBytes(hash_keccak256_bytes(&self.paymaster_and_data).to_vec()),
+                ];
+
+                return abi_encode_params(
+                    "address,uint256,bytes32,bytes32,uint256,uint256,uint256,uint256,uint256,bytes32",
+                    &args,
+                )
+                .expect("static-layout");
+            }
+        }
+
+        /// keccak256(abi.encode(keccak256(pack(op)), entryPoint, chainId)),
+        /// the hash `EntryPoint.getUserOpHash` returns and wallets sign.
+        pub fn user_op_hash(op: &UserOperation, entry_point: &[u8; 20], chain_id: u64) -> [u8; 32] {
+            let args = [
+                AbiValue::FixedBytes(hash_keccak256_bytes(&op.pack()).to_vec()),
+                AbiValue::Address(*entry_point),
+                AbiValue::Uint(U256::from(chain_id)),
+            ];
+
+            let encoded =
+                abi_encode_params("bytes32,address,uint256", &args).expect("static-layout");
+            return hash_keccak256_bytes(&encoded);
+        }
+    }
+
+    pub mod eip1271 {
+        use crate::crypto::ethereum::{abi_encode, AbiValue};
+
+        /// `isValidSignature(bytes32,bytes)` selector, which is also the value
+        /// a contract returns to accept a signature.
+        pub const MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum Eip1271Error {
+            /// Return data is not a single 32-byte word.
+            InvalidLength(usize),
+            /// A bytes4 is left-aligned, the other 28 bytes must be zero.
+            DirtyPadding,
+        }
+
+        /// Call data for `isValidSignature(hash, signature)`, to send with
+        /// eth_call to the wallet contract.
+        pub fn is_valid_signature_calldata(hash: &[u8; 32], signature: &[u8]) -> Vec<u8> {
+            let args = [
+                AbiValue::FixedBytes(hash.to_vec()),
+                AbiValue::Bytes(signature.to_vec()),
+            ];
+            return abi_encode("isValidSignature(bytes32,bytes)", &args).expect("static-layout");
+        }
+
+        /// True if the eth_call result is the magic value. Any other
+        /// well-formed bytes4, including zero, means the signature is
+        /// rejected.
+        pub fn parse_is_valid_signature_result(result: &[u8]) -> Result<bool, Eip1271Error> {
+            if result.len() != 32 {
+                return Err(Eip1271Error::InvalidLength(result.len()));
+            }
+            if result[4..].iter().any(|b| *b != 0) {
+                return Err(Eip1271Error::DirtyPadding);
+            }
+
+            return Ok(result[..4] == MAGIC_VALUE);
+        }
+    }
+
+    pub mod wei {
+        use crate::u256::U256;
+
+        /// Wei per ether.
+        const ETHER_DECIMALS: usize = 18;
+
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum WeiError {
+            Overflow,
+            /// Empty string, or nothing but a decimal point.
+            Empty,
+            InvalidCharacter(char),
+            /// More than 18 fractional digits, finer than one wei.
+            TooPrecise(usize),
+        }
+
+        /// An amount in wei. Arithmetic is checked, never wrapping.
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Wei(pub U256);
+
+        impl Wei {
+            pub fn checked_add(&self, other: &Wei) -> Option<Wei> {
+                return self.0.checked_add(&other.0).map(Wei);
+            }
+
+            pub fn checked_mul_u64(&self, n: u64) -> Option<Wei> {
+                return self.0.checked_mul(&U256::new(n)).map(Wei);
+            }
+
+            /// Parses a decimal ether amount, "1.5" -> 1.5e18 wei. Amounts that
+            /// aren't a whole number of wei are rejected rather than rounded.
+            pub fn from_ether_str(s: &str) -> Result<Wei, WeiError> {
+                let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+                if int.is_empty() && frac.is_empty() {
+                    return Err(WeiError::Empty);
+                }
+                if frac.len() > ETHER_DECIMALS {
+                    return Err(WeiError::TooPrecise(frac.len()));
+                }
+
+                // The fraction padded to 18 digits is the sub-ether wei count
+                let mut v = U256::zero();
+                let padding = std::iter::repeat_n('0', ETHER_DECIMALS - frac.len());
+                for c in int.chars().chain(frac.chars()).chain(padding) {
+                    let digit = c.to_digit(10).ok_or(WeiError::InvalidCharacter(c))?;
+                    v = v
+                        .checked_mul(&U256::new(10))
+                        .and_then(|v| v.checked_add(&U256::new(digit as u64)))
+                        .ok_or(WeiError::Overflow)?;
+                }
+
+                return Ok(Wei(v));
+            }
+
+            /// Formats as ether with exactly `decimals` fractional digits
+            /// (at most 18). Extra digits are truncated, not rounded.
+            pub fn to_ether_string(&self, decimals: usize) -> String {
+                let digits = format!(
+                    "{:0>width$}",
+                    self.0.to_dec_string(),
+                    width = ETHER_DECIMALS + 1
+                );
+                let (int, frac) = digits.split_at(digits.len() - ETHER_DECIMALS);
+
+                let decimals = decimals.min(ETHER_DECIMALS);
+                if decimals == 0 {
+                    return int.to_string();
+                }
+                return format!("{}.{}", int, &frac[..decimals]);
+            }
+        }
+
+        /// value + gas_limit * max_fee_per_gas, the balance an EIP-1559
+        /// transaction needs up front.
+        pub fn max_cost(
+            value: &Wei,
+            gas_limit: u64,
+            max_fee_per_gas: &Wei,
+        ) -> Result<Wei, WeiError> {
+            return max_fee_per_gas
+                .checked_mul_u64(gas_limit)
+                .and_then(|fee| fee.checked_add(value))
+                .ok_or(WeiError::Overflow);
+        }
+    }
+}
+
+pub mod kdf {
+    use sha2::{Digest, Sha256};
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum KdfError {
+        /// More than 2^32 - 1 hash blocks would be needed, the 32-bit counter
+        /// would wrap.
+        OutputTooLong(usize),
+    }
+
+    /// ANSI X9.63 KDF with SHA-256:
+    ///
+    /// K(i) = SHA256(Z || counter_i || SharedInfo), counter_i = i as 32-bit BE
+    ///
+    /// The counter starts at 1 and the blocks are concatenated and truncated
+    /// to `out_len`.
+    pub fn x963_sha256(
+        shared: &[u8],
+        shared_info: &[u8],
+        out_len: usize,
+    ) -> Result<Vec<u8>, KdfError> {
+        return counter_kdf_sha256(out_len, |counter| {
+            let mut hasher = Sha256::new();
+            hasher.update(shared);
+            hasher.update(counter);
+            hasher.update(shared_info);
+            return hasher.finalize().into();
+        });
+    }
+
+    /// NIST SP 800-56A single-step KDF (ConcatKDF) with SHA-256. Same as X9.63
+    /// except the counter goes first:
+    ///
+    /// K(i) = SHA256(counter_i || Z || OtherInfo)
+    pub fn concat_kdf_sha256(
+        z: &[u8],
+        other_info: &[u8],
+        out_len: usize,
+    ) -> Result<Vec<u8>, KdfError> {
+        return counter_kdf_sha256(out_len, |counter| {
+            let mut hasher = Sha256::new();
+            hasher.update(counter);
+            hasher.update(z);
+            hasher.update(other_info);
+            return hasher.finalize().into();
+        });
+    }
+
+    fn counter_kdf_sha256<F>(out_len: usize, block: F) -> Result<Vec<u8>, KdfError>
+    where
+        F: Fn(&[u8; 4]) -> [u8; 32],
+    {
+        let blocks = out_len.div_ceil(32);
+        if blocks as u64 > u32::MAX as u64 {
+            return Err(KdfError::OutputTooLong(out_len));
+        }
+
+        let mut r = Vec::with_capacity(blocks * 32);
+        for counter in 1..=blocks as u32 {
+            r.extend(block(&counter.to_be_bytes()));
+        }
+        r.truncate(out_len);
+
+        return Ok(r);
     }
 }
 
@@ -80,25 +1023,858 @@ pub mod secp256k1_prod {
 
 #[cfg(test)]
 mod tests {
-    use crate::crypto::ethereum;
+    use crate::base16;
+    use crate::crypto::ethereum::eip1271::{self, Eip1271Error};
+    use crate::crypto::ethereum::erc4337::{self, UserOperation};
+    use crate::crypto::ethereum::wei::{self, Wei, WeiError};
+    use crate::crypto::ethereum::{
+        self, AbiError, AbiValue, AddressError, AddressRowError, EnsError, IcapError, PubKeyError,
+    };
+    use crate::crypto::hashing;
+    use crate::crypto::kdf::{self, KdfError};
     use crate::crypto::secp256k1_prod as secp256k1;
+    use crate::rng::TestRng;
+    use crate::u256::U256;
+    use rand::RngCore;
+    use std::str::FromStr;
 
     #[test]
     fn ethereum_check_sum() {
         let ad = String::from("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359");
-        let r = ethereum::check_sum(&ad);
+        let r = ethereum::check_sum(&ad).unwrap();
 
         let e = String::from("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359");
         assert_eq!(e, r);
+
+        assert_eq!(
+            ethereum::check_sum("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d35"),
+            Err(AddressError::InvalidLength(41))
+        );
+        assert_eq!(
+            ethereum::check_sum("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d35é"),
+            Err(AddressError::InvalidLength(43))
+        );
+        assert_eq!(
+            ethereum::check_sum("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d35z"),
+            Err(AddressError::InvalidCharacter('z'))
+        );
+        assert_eq!(
+            ethereum::check_sum("fb6916095ca1df60bb79ce92ce3ea74c37c5d35900"),
+            Err(AddressError::MissingPrefix)
+        );
     }
 
     #[test]
     fn ethereum_address() {
         let pr_n = "51bb0a7f49284110c62e4268baa3cfad4a81edcd6e6ec3b2a8ef97f1e3754491";
         let pub_key = secp256k1::get_public_key(pr_n);
-        let r = ethereum::derive_address(&pub_key);
+        let r = ethereum::derive_address(&pub_key).unwrap();
 
         let e = "0x7aa6D878Ac2d1271fCD010802f7e09fAcd8528bf";
         assert_eq!(e, r);
+
+        assert_eq!(
+            ethereum::derive_address(&pub_key[..128]),
+            Err(PubKeyError::InvalidLength(128))
+        );
+        assert_eq!(
+            ethereum::derive_address(&format!("02{}", &pub_key[2..])),
+            Err(PubKeyError::NotUncompressed)
+        );
+        assert_eq!(
+            ethereum::derive_address(&format!("{}g", &pub_key[..129])),
+            Err(PubKeyError::InvalidCharacter('g'))
+        );
+    }
+
+    #[test]
+    fn ethereum_check_sum_eip55_vectors() {
+        let vectors = [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ];
+
+        for e in vectors {
+            assert_eq!(ethereum::check_sum(&e.to_lowercase()).unwrap(), e);
+            assert_eq!(
+                ethereum::check_sum_with_chain_id(&e.to_lowercase(), None).unwrap(),
+                e
+            );
+            assert!(ethereum::is_valid_checksum_with_chain_id(e, None));
+        }
+    }
+
+    #[test]
+    fn ethereum_check_sum_eip1191_vectors() {
+        let vectors = [
+            (30, "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD"),
+            (30, "0xFb6916095cA1Df60bb79ce92cE3EA74c37c5d359"),
+            (30, "0xDBF03B407c01E7CD3cBea99509D93F8Dddc8C6FB"),
+            (30, "0xD1220A0Cf47c7B9BE7a2e6ba89F429762E7B9adB"),
+            (31, "0x5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd"),
+            (31, "0xFb6916095CA1dF60bb79CE92ce3Ea74C37c5D359"),
+            (31, "0xdbF03B407C01E7cd3cbEa99509D93f8dDDc8C6fB"),
+            (31, "0xd1220a0CF47c7B9Be7A2E6Ba89f429762E7b9adB"),
+        ];
+
+        for (chain_id, e) in vectors {
+            let r = ethereum::check_sum_with_chain_id(&e.to_lowercase(), Some(chain_id)).unwrap();
+            assert_eq!(r, e);
+            assert!(ethereum::is_valid_checksum_with_chain_id(e, Some(chain_id)));
+            assert!(!ethereum::is_valid_checksum_with_chain_id(e, None));
+        }
+    }
+
+    #[test]
+    fn ethereum_check_sum_with_chain_id_errors() {
+        assert_eq!(
+            ethereum::check_sum_with_chain_id("fb6916095ca1df60bb79ce92ce3ea74c37c5d359", None),
+            Err(AddressError::MissingPrefix)
+        );
+        assert_eq!(
+            ethereum::check_sum_with_chain_id("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d3", Some(30)),
+            Err(AddressError::InvalidLength(40))
+        );
+        assert_eq!(
+            ethereum::check_sum_with_chain_id(
+                "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d35g",
+                Some(30)
+            ),
+            Err(AddressError::InvalidCharacter('g'))
+        );
+        assert!(!ethereum::is_valid_checksum_with_chain_id(
+            "0x1234",
+            Some(30)
+        ));
+    }
+
+    fn words(selector: &str, ws: &[&str]) -> String {
+        return format!("{}{}", selector, ws.concat());
+    }
+
+    #[test]
+    fn ethereum_abi_encode_erc20_transfer() {
+        let mut to = [0; 20];
+        to.copy_from_slice(&base16::decode_string(
+            "7aa6d878ac2d1271fcd010802f7e09facd8528bf",
+        ));
+        let amount = U256::from_str("0xde0b6b3a7640000").unwrap();
+
+        let r = ethereum::abi_encode(
+            "transfer(address,uint256)",
+            &[AbiValue::Address(to), AbiValue::Uint(amount)],
+        )
+        .unwrap();
+
+        let e = words(
+            "a9059cbb",
+            &[
+                "0000000000000000000000007aa6d878ac2d1271fcd010802f7e09facd8528bf",
+                "0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+            ],
+        );
+        assert_eq!(base16::encode_bytes(&r), e);
+    }
+
+    #[test]
+    fn ethereum_abi_encode_static() {
+        let r = ethereum::abi_encode(
+            "baz(uint32,bool)",
+            &[AbiValue::Uint(U256::new(0x45)), AbiValue::Bool(true)],
+        )
+        .unwrap();
+
+        let e = words(
+            "cdcd77c0",
+            &[
+                "0000000000000000000000000000000000000000000000000000000000000045",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+            ],
+        );
+        assert_eq!(base16::encode_bytes(&r), e);
+
+        let r = ethereum::abi_encode(
+            "bar(bytes3[2])",
+            &[AbiValue::Array(vec![
+                AbiValue::FixedBytes(b"abc".to_vec()),
+                AbiValue::FixedBytes(b"def".to_vec()),
+            ])],
+        )
+        .unwrap();
+
+        let e = words(
+            "fce353f6",
+            &[
+                "6162630000000000000000000000000000000000000000000000000000000000",
+                "6465660000000000000000000000000000000000000000000000000000000000",
+            ],
+        );
+        assert_eq!(base16::encode_bytes(&r), e);
+    }
+
+    #[test]
+    fn ethereum_abi_encode_dynamic() {
+        let r = ethereum::abi_encode(
+            "sam(bytes,bool,uint256[])",
+            &[
+                AbiValue::Bytes(b"dave".to_vec()),
+                AbiValue::Bool(true),
+                AbiValue::Array(vec![
+                    AbiValue::Uint(U256::new(0x1)),
+                    AbiValue::Uint(U256::new(0x2)),
+                    AbiValue::Uint(U256::new(0x3)),
+                ]),
+            ],
+        )
+        .unwrap();
+
+        let e = words(
+            "a5643bf2",
+            &[
+                "0000000000000000000000000000000000000000000000000000000000000060",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "00000000000000000000000000000000000000000000000000000000000000a0",
+                "0000000000000000000000000000000000000000000000000000000000000004",
+                "6461766500000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+            ],
+        );
+        assert_eq!(base16::encode_bytes(&r), e);
+
+        let r = ethereum::abi_encode(
+            "f(uint,uint32[],bytes10,bytes)",
+            &[
+                AbiValue::Uint(U256::new(0x123)),
+                AbiValue::Array(vec![
+                    AbiValue::Uint(U256::new(0x456)),
+                    AbiValue::Uint(U256::new(0x789)),
+                ]),
+                AbiValue::FixedBytes(b"1234567890".to_vec()),
+                AbiValue::Bytes(b"Hello, world!".to_vec()),
+            ],
+        )
+        .unwrap();
+
+        let e = words(
+            "8be65246",
+            &[
+                "0000000000000000000000000000000000000000000000000000000000000123",
+                "0000000000000000000000000000000000000000000000000000000000000080",
+                "3132333435363738393000000000000000000000000000000000000000000000",
+                "00000000000000000000000000000000000000000000000000000000000000e0",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000456",
+                "0000000000000000000000000000000000000000000000000000000000000789",
+                "000000000000000000000000000000000000000000000000000000000000000d",
+                "48656c6c6f2c20776f726c642100000000000000000000000000000000000000",
+            ],
+        );
+        assert_eq!(base16::encode_bytes(&r), e);
+    }
+
+    #[test]
+    fn ethereum_abi_encode_int() {
+        let r = ethereum::abi_encode("f(int8)", &[AbiValue::Int(-1)]).unwrap();
+        assert_eq!(
+            base16::encode_bytes(&r[4..]),
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+        );
+
+        assert_eq!(
+            ethereum::abi_encode("f(int8)", &[AbiValue::Int(128)]),
+            Err(AbiError::OutOfRange {
+                index: 0,
+                expected: String::from("int8")
+            })
+        );
+    }
+
+    #[test]
+    fn ethereum_abi_encode_errors() {
+        assert_eq!(
+            ethereum::abi_encode("transfer(address,uint256)", &[AbiValue::Bool(true)]),
+            Err(AbiError::ArgumentCount {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert_eq!(
+            ethereum::abi_encode(
+                "transfer(address,uint256)",
+                &[AbiValue::Address([0; 20]), AbiValue::Bool(true)]
+            ),
+            Err(AbiError::TypeMismatch {
+                index: 1,
+                expected: String::from("uint256")
+            })
+        );
+        assert_eq!(
+            ethereum::abi_encode("f(uint8)", &[AbiValue::Uint(U256::new(0x100))]),
+            Err(AbiError::OutOfRange {
+                index: 0,
+                expected: String::from("uint8")
+            })
+        );
+        assert_eq!(
+            ethereum::abi_encode("f(bytes3[2])", &[AbiValue::Array(vec![])]),
+            Err(AbiError::TypeMismatch {
+                index: 0,
+                expected: String::from("bytes3[2]")
+            })
+        );
+        assert_eq!(
+            ethereum::abi_encode("f(uint7)", &[]),
+            Err(AbiError::UnsupportedType(String::from("uint7")))
+        );
+        // 32·k bytes of head overflows usize, before the length is checked
+        let huge = format!("uint256[{}]", usize::MAX / 16);
+        assert_eq!(
+            ethereum::abi_encode(&format!("f({})", huge), &[AbiValue::Array(vec![])]),
+            Err(AbiError::UnsupportedType(huge))
+        );
+        let half = format!("uint256[{}]", usize::MAX / 48);
+        assert_eq!(
+            ethereum::abi_encode(
+                &format!("f({},{})", half, half),
+                &[AbiValue::Array(vec![]), AbiValue::Array(vec![])]
+            ),
+            Err(AbiError::UnsupportedType(half))
+        );
+        assert_eq!(
+            ethereum::abi_encode("f(uint256[][])", &[]),
+            Err(AbiError::UnsupportedType(String::from("uint256[][]")))
+        );
+        assert_eq!(
+            ethereum::abi_encode("transfer(address", &[]),
+            Err(AbiError::InvalidSignature(String::from("transfer(address")))
+        );
+    }
+
+    #[test]
+    fn kdf_x963_sha256_vectors() {
+        let z = base16::decode_string("96c05619d56c328ab95fe84b18264b08725b85e33fd34f08");
+        let r = kdf::x963_sha256(&z, &[], 16).unwrap();
+        assert_eq!(base16::encode_bytes(&r), "443024c3dae66b95e6f5670601558f71");
+
+        let z = base16::decode_string("22518b10e70f2a3f243810ae3254139efbee04aa57c7af7d");
+        let info = base16::decode_string("75eef81aa3041e33b80971203d2c0c52");
+        let r = kdf::x963_sha256(&z, &info, 128).unwrap();
+        assert_eq!(
+            base16::encode_bytes(&r),
+            "c498af77161cc59f2962b9a713e2b215152d139766ce34a776df11866a69bf2e\
+             52a13d9c7c6fc878c50c5ea0bc7b00e0da2447cfd874f6cf92f30d0097111485\
+             500c90c3af8b487872d04685d14c8d1dc8d7fa08beb0ce0ababc11f0bd496269\
+             142d43525a78e5bc79a17f59676a5706dc54d54d4d1f0bd7e386128ec26afc21"
+        );
+    }
+
+    #[test]
+    fn kdf_concat_kdf_sha256() {
+        let z = base16::decode_string(
+            "52169af5c485dcc2321eb8d26d5efa21fb9b93c98e38412ee2484cf14f0d0d23",
+        );
+        let info = base16::decode_string(
+            "a1b2c3d4e53728157e634612c12d6d5223e204aeea4341565369647bd184bcd2\
+             46f72971f292badaa2fe4124612cba",
+        );
+        let r = kdf::concat_kdf_sha256(&z, &info, 16).unwrap();
+        assert_eq!(base16::encode_bytes(&r), "1c3bc9e7c4547c5191c0d478cccaed55");
+
+        // Output is a prefix of the longer output
+        let long = kdf::concat_kdf_sha256(&z, &info, 80).unwrap();
+        assert_eq!(long.len(), 80);
+        assert_eq!(&long[..16], &r[..]);
+    }
+
+    #[test]
+    fn kdf_output_too_long() {
+        let too_long = 32 * (u32::MAX as usize) + 1;
+        assert_eq!(
+            kdf::x963_sha256(&[1], &[], too_long),
+            Err(KdfError::OutputTooLong(too_long))
+        );
+        assert_eq!(
+            kdf::concat_kdf_sha256(&[1], &[], too_long),
+            Err(KdfError::OutputTooLong(too_long))
+        );
+        assert_eq!(kdf::x963_sha256(&[1], &[], 0), Ok(vec![]));
+    }
+
+    #[test]
+    fn ethereum_write_checksummed() {
+        let addresses = [
+            "0x0000000000000000000000000000000000000000",
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+            "0xdbf03b407c01e7cd3cbea99509d93f8dddc8c6fb",
+            "0xd1220a0cf47c7b9be7a2e6ba89f429762e7b9adb",
+            "0xffffffffffffffffffffffffffffffffffffffff",
+        ];
+
+        for ad in addresses {
+            let mut bytes = [0; 20];
+            bytes.copy_from_slice(&base16::decode_string(&ad[2..]));
+
+            let mut out = [0; 42];
+            ethereum::write_checksummed(&bytes, &mut out);
+            assert_eq!(
+                std::str::from_utf8(&out).unwrap(),
+                ethereum::check_sum(ad).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn ethereum_normalize_addresses() {
+        let rows = [
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea",
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaeg",
+            "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+        ];
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+        let err = |row, error| Err(AddressRowError { row, error });
+        assert_eq!(
+            ethereum::normalize_addresses(rows.into_iter()),
+            vec![
+                Ok(checksummed.to_string()),
+                Ok(checksummed.to_string()),
+                Ok(checksummed.to_string()),
+                err(3, AddressError::InvalidChecksum),
+                err(4, AddressError::MissingPrefix),
+                err(5, AddressError::InvalidLength(40)),
+                err(6, AddressError::InvalidCharacter('g')),
+                Ok("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ethereum_icap_round_trip() {
+        let vectors = [
+            (
+                "0x00c5496aee77c1ba1f0854206a26dda82a81d6d8",
+                "XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZS",
+            ),
+            (
+                "0x0000000000000000000000000000000000000000",
+                "XE50000000000000000000000000000000",
+            ),
+            (
+                "0x0000000000000000000000000000000000000001",
+                "XE23000000000000000000000000000001",
+            ),
+            (
+                "0x0000000000000000000000000000000000001234",
+                "XE980000000000000000000000000003LG",
+            ),
+            (
+                "0x07ffffffffffffffffffffffffffffffffffffff",
+                "XE78XN3J2TOA0870SUII7VSS42QMU0NQWV",
+            ),
+        ];
+        for (hex, icap) in vectors {
+            let address = address_bytes(hex);
+            assert_eq!(ethereum::address_to_icap(&address), icap);
+            assert_eq!(ethereum::address_from_icap(icap), Ok(address));
+            assert_eq!(
+                ethereum::address_from_icap(&icap.to_lowercase()),
+                Ok(address)
+            );
+        }
+
+        // Too large for direct ICAP, so basic
+        let address = address_bytes("0x52908400098527886e0f7030069857d2e4169ee7");
+        let basic = "XE299N77R1E7DZBBOF2AM8TIHUU16499IHZ";
+        assert_eq!(ethereum::address_to_icap(&address), basic);
+        assert_eq!(ethereum::address_from_icap(basic), Ok(address));
+
+        let mut rng = TestRng::new(274);
+        for _ in 0..200 {
+            let mut address = [0; 20];
+            rng.fill_bytes(&mut address);
+            // Up to 7 leading zero bytes, so both forms come up
+            address[..(rng.next_u32() % 8) as usize].fill(0);
+            let icap = ethereum::address_to_icap(&address);
+            assert!(icap.len() == 34 || icap.len() == 35, "{}", icap);
+            assert_eq!(ethereum::address_from_icap(&icap), Ok(address), "{}", icap);
+        }
+    }
+
+    #[test]
+    fn ethereum_icap_errors() {
+        let icap = "XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZS";
+
+        // Every single-character change is caught by the check digits
+        for i in 2..icap.len() {
+            let mut corrupted = icap.as_bytes().to_vec();
+            corrupted[i] = if corrupted[i] == b'1' { b'2' } else { b'1' };
+            let corrupted = String::from_utf8(corrupted).unwrap();
+            assert_eq!(
+                ethereum::address_from_icap(&corrupted),
+                Err(IcapError::InvalidChecksum),
+                "{}",
+                corrupted
+            );
+        }
+        assert_eq!(
+            ethereum::address_from_icap("XE3738O073KYGTWWZN0F2WZ0R8PX5ZPPZS"),
+            Err(IcapError::InvalidChecksum)
+        );
+
+        assert_eq!(
+            ethereum::address_from_icap("GB7338O073KYGTWWZN0F2WZ0R8PX5ZPPZS"),
+            Err(IcapError::InvalidPrefix)
+        );
+        assert_eq!(
+            ethereum::address_from_icap("XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZ"),
+            Err(IcapError::InvalidLength(33))
+        );
+        assert_eq!(
+            ethereum::address_from_icap("XE81ETHXREGGAVOFYORK"),
+            Err(IcapError::InvalidLength(20))
+        );
+        assert_eq!(
+            ethereum::address_from_icap("XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZ-"),
+            Err(IcapError::InvalidCharacter('-'))
+        );
+        // 36^31 - 1, a valid basic ICAP that is 2^160 or more
+        assert_eq!(
+            ethereum::address_from_icap("XE54ZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZ"),
+            Err(IcapError::Overflow)
+        );
+    }
+
+    #[test]
+    fn ethereum_address_from_any() {
+        let address = address_bytes("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+        for input in [
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+            "  0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed\n",
+        ] {
+            assert_eq!(ethereum::address_from_any(input), Ok(address));
+        }
+        assert_eq!(
+            ethereum::address_from_any("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(AddressError::InvalidChecksum)
+        );
+
+        let address = address_bytes("0x00c5496aee77c1ba1f0854206a26dda82a81d6d8");
+        assert_eq!(
+            ethereum::address_from_any(" xe7338o073kygtwwzn0f2wz0r8px5zppzs "),
+            Ok(address)
+        );
+        assert_eq!(
+            ethereum::address_from_any("XE7438O073KYGTWWZN0F2WZ0R8PX5ZPPZS"),
+            Err(AddressError::Icap(IcapError::InvalidChecksum))
+        );
+        assert_eq!(
+            ethereum::address_from_any("00c5496aee77c1ba1f0854206a26dda82a81d6d8"),
+            Err(AddressError::MissingPrefix)
+        );
+    }
+
+    #[test]
+    fn hashing_write_digest_hex() {
+        for input in [&b""[..], b"abc", &[0xff; 100]] {
+            let digest = hashing::hash_keccak256_bytes(input);
+
+            let mut out = [0; 64];
+            hashing::write_digest_hex(&digest, &mut out);
+            assert_eq!(
+                std::str::from_utf8(&out).unwrap(),
+                hashing::hash_keccak256(input).to_lowercase()
+            );
+        }
+    }
+
+    fn fixture_user_op() -> UserOperation {
+        let mut sender = [0; 20];
+        sender.copy_from_slice(&base16::decode_string(
+            "7aa6d878ac2d1271fcd010802f7e09facd8528bf",
+        ));
+
+        return UserOperation {
+            sender,
+            nonce: U256::new(0x1),
+            init_code: vec![],
+            call_data: vec![],
+            call_gas_limit: U256::new(0x2),
+            verification_gas_limit: U256::new(0x3),
+            pre_verification_gas: U256::new(0x4),
+            max_fee_per_gas: U256::new(0x5),
+            max_priority_fee_per_gas: U256::new(0x6),
+            paymaster_and_data: vec![],
+            signature: vec![0xaa; 65],
+        };
+    }
+
+    #[test]
+    fn ethereum_erc4337_pack() {
+        let op = fixture_user_op();
+
+        // keccak256 of the empty string stands in for each empty dynamic field
+        let empty = "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+        let e = words(
+            "",
+            &[
+                "0000000000000000000000007aa6d878ac2d1271fcd010802f7e09facd8528bf",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                empty,
+                empty,
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "0000000000000000000000000000000000000000000000000000000000000004",
+                "0000000000000000000000000000000000000000000000000000000000000005",
+                "0000000000000000000000000000000000000000000000000000000000000006",
+                empty,
+            ],
+        );
+        assert_eq!(base16::encode_bytes(&op.pack()), e);
+    }
+
+    #[test]
+    fn ethereum_erc4337_user_op_hash() {
+        let mut entry_point = [0; 20];
+        entry_point.copy_from_slice(&base16::decode_string(
+            "5ff137d4b0fdcd49dca30c7cf57e578a026d2789",
+        ));
+
+        let op = fixture_user_op();
+        let h = erc4337::user_op_hash(&op, &entry_point, 1);
+
+        // getUserOpHash of the v0.6 EntryPoint and SDK, computed outside
+        // this crate
+        assert_eq!(
+            base16::encode_bytes(&h),
+            "fd378e5d4e116721c0b9e470f52565a2b93442c3ef00cde61b83d653cf8d2dfe"
+        );
+
+        // The signature is excluded, everything else is bound
+        let mut signed = op.clone();
+        signed.signature = vec![0xbb; 65];
+        assert_eq!(erc4337::user_op_hash(&signed, &entry_point, 1), h);
+
+        assert_ne!(erc4337::user_op_hash(&op, &entry_point, 137), h);
+        assert_ne!(erc4337::user_op_hash(&op, &[0; 20], 1), h);
+
+        let mut changed = op.clone();
+        changed.call_data = vec![0x01];
+        assert_ne!(erc4337::user_op_hash(&changed, &entry_point, 1), h);
+    }
+
+    fn address_bytes(ad: &str) -> [u8; 20] {
+        let mut r = [0; 20];
+        r.copy_from_slice(&base16::decode_string(&ad[2..].to_lowercase()));
+        return r;
+    }
+
+    #[test]
+    fn ethereum_address_similarity() {
+        let real = address_bytes("0x7aa6D878Ac2d1271fCD010802f7e09fAcd8528bf");
+
+        // Same address, different casing
+        let lower = address_bytes("0x7aa6d878ac2d1271fcd010802f7e09facd8528bf");
+        let sim = ethereum::address_similarity(&real, &lower);
+        assert_eq!(sim.prefix_nibbles, 40);
+        assert_eq!(sim.suffix_nibbles, 40);
+        assert_eq!(sim.hamming_distance, 0);
+        assert!(!ethereum::is_suspicious_lookalike(&real, &lower, 4, 4));
+
+        // Poisoned: first 5 and last 4 digits match, middle differs
+        let poisoned = address_bytes("0x7aa6d000000000000000000000000000000028bf");
+        let sim = ethereum::address_similarity(&real, &poisoned);
+        assert_eq!(sim.prefix_nibbles, 5);
+        assert_eq!(sim.suffix_nibbles, 4);
+        assert_eq!(sim.hamming_distance, 27);
+        assert!(ethereum::is_suspicious_lookalike(&real, &poisoned, 4, 4));
+        assert!(!ethereum::is_suspicious_lookalike(&real, &poisoned, 6, 4));
+        assert!(!ethereum::is_suspicious_lookalike(&real, &poisoned, 4, 5));
+
+        // Matching on the high nibble only
+        let half = address_bytes("0x7aa6d978ac2d1271fcd010802f7e09facd8528b0");
+        let sim = ethereum::address_similarity(&real, &half);
+        assert_eq!(sim.prefix_nibbles, 5);
+        assert_eq!(sim.suffix_nibbles, 0);
+        assert_eq!(sim.hamming_distance, 2);
+
+        // Unrelated
+        let other = address_bytes("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359");
+        let sim = ethereum::address_similarity(&real, &other);
+        assert_eq!(sim.prefix_nibbles, 0);
+        assert_eq!(sim.suffix_nibbles, 0);
+        assert!(!ethereum::is_suspicious_lookalike(&real, &other, 1, 1));
+    }
+
+    #[test]
+    fn ethereum_ens_namehash() {
+        assert_eq!(ethereum::namehash(""), [0; 32]);
+        assert_eq!(
+            base16::encode_bytes(&ethereum::namehash("eth")),
+            "93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+        );
+        assert_eq!(
+            base16::encode_bytes(&ethereum::namehash("addr.reverse")),
+            "91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2"
+        );
+
+        let address = address_bytes("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        assert_eq!(
+            base16::encode_bytes(&ethereum::reverse_node(&address)),
+            "7aef81fbd30c83431369026d62ee533af8b69f246b63d75b40fe223346e6fa9a"
+        );
+    }
+
+    #[test]
+    fn ethereum_ens_dns_encode_name() {
+        assert_eq!(ethereum::dns_encode_name(""), Ok(vec![0]));
+        assert_eq!(
+            ethereum::dns_encode_name("vitalik.eth"),
+            Ok(b"\x07vitalik\x03eth\x00".to_vec())
+        );
+
+        let label = "a".repeat(63);
+        let mut expected = vec![63];
+        expected.extend(label.as_bytes());
+        expected.push(0);
+        assert_eq!(ethereum::dns_encode_name(&label), Ok(expected));
+
+        assert_eq!(
+            ethereum::dns_encode_name(&"a".repeat(64)),
+            Err(EnsError::LabelTooLong(64))
+        );
+        for name in ["vitalik..eth", ".eth", "eth.", "."] {
+            assert_eq!(ethereum::dns_encode_name(name), Err(EnsError::EmptyLabel));
+        }
+
+        // Four 63-byte labels: 4 * 64 + 1 = 257 bytes
+        let long = [label.as_str(); 4].join(".");
+        assert_eq!(
+            ethereum::dns_encode_name(&long),
+            Err(EnsError::NameTooLong(257))
+        );
+    }
+
+    #[test]
+    fn ethereum_eip1271_calldata() {
+        let hash = [0xab; 32];
+        let signature = [0x11; 65];
+        let calldata = eip1271::is_valid_signature_calldata(&hash, &signature);
+
+        let hash_word = "ab".repeat(32);
+        let sig_word = "11".repeat(32);
+        let sig_tail = format!("11{}", "0".repeat(62));
+        assert_eq!(
+            base16::encode_bytes(&calldata),
+            words(
+                "1626ba7e",
+                &[
+                    &hash_word,
+                    "0000000000000000000000000000000000000000000000000000000000000040",
+                    "0000000000000000000000000000000000000000000000000000000000000041",
+                    &sig_word,
+                    &sig_word,
+                    &sig_tail,
+                ]
+            )
+        );
+
+        // Empty signature: just the offset and a zero length
+        let calldata = eip1271::is_valid_signature_calldata(&hash, &[]);
+        assert_eq!(calldata.len(), 4 + 3 * 32);
+    }
+
+    #[test]
+    fn ethereum_eip1271_result() {
+        let mut magic = [0; 32];
+        magic[..4].copy_from_slice(&eip1271::MAGIC_VALUE);
+        assert_eq!(eip1271::parse_is_valid_signature_result(&magic), Ok(true));
+        assert_eq!(
+            eip1271::parse_is_valid_signature_result(&[0; 32]),
+            Ok(false)
+        );
+
+        let mut other = [0; 32];
+        other[..4].copy_from_slice(&[0xff; 4]);
+        assert_eq!(eip1271::parse_is_valid_signature_result(&other), Ok(false));
+
+        assert_eq!(
+            eip1271::parse_is_valid_signature_result(&magic[..4]),
+            Err(Eip1271Error::InvalidLength(4))
+        );
+        assert_eq!(
+            eip1271::parse_is_valid_signature_result(&[0; 64]),
+            Err(Eip1271Error::InvalidLength(64))
+        );
+        magic[31] = 1;
+        assert_eq!(
+            eip1271::parse_is_valid_signature_result(&magic),
+            Err(Eip1271Error::DirtyPadding)
+        );
+    }
+
+    #[test]
+    fn ethereum_wei_parse_and_format() {
+        let one_wei = Wei::from_ether_str("0.000000000000000001").unwrap();
+        assert_eq!(one_wei, Wei(U256::one()));
+        assert_eq!(one_wei.to_ether_string(18), "0.000000000000000001");
+        assert_eq!(one_wei.to_ether_string(2), "0.00");
+
+        let one = Wei::from_ether_str("1").unwrap();
+        assert_eq!(one.0, U256::from_str("de0b6b3a7640000").unwrap());
+        assert_eq!(one.to_ether_string(0), "1");
+        assert_eq!(Wei::from_ether_str("1.").unwrap(), one);
+
+        let amount = Wei::from_ether_str("1234.5").unwrap();
+        assert_eq!(amount.0, U256::from_str("42ec210956b3ba0000").unwrap());
+        assert_eq!(amount.to_ether_string(3), "1234.500");
+        assert_eq!(Wei::from_ether_str(".5").unwrap().to_ether_string(1), "0.5");
+
+        assert_eq!(
+            Wei::from_ether_str("1.0000000000000000001"),
+            Err(WeiError::TooPrecise(19))
+        );
+        assert_eq!(Wei::from_ether_str("."), Err(WeiError::Empty));
+        assert_eq!(
+            Wei::from_ether_str("-1"),
+            Err(WeiError::InvalidCharacter('-'))
+        );
+        assert_eq!(
+            Wei::from_ether_str("1e18"),
+            Err(WeiError::InvalidCharacter('e'))
+        );
+        // 2^256 wei is about 1.16e59 ether
+        assert_eq!(
+            Wei::from_ether_str(&"9".repeat(60)),
+            Err(WeiError::Overflow)
+        );
+    }
+
+    #[test]
+    fn ethereum_wei_max_cost() {
+        let value = Wei::from_ether_str("1").unwrap();
+        let fee = Wei::from_ether_str("0.00000003").unwrap(); // 30 gwei
+        let cost = wei::max_cost(&value, 21000, &fee).unwrap();
+        assert_eq!(cost.to_ether_string(18), "1.000630000000000000");
+
+        let max = Wei(U256::from_be_bytes(&[0xff; 32]));
+        assert_eq!(max.checked_add(&Wei(U256::one())), None);
+        assert_eq!(max.checked_mul_u64(2), None);
+        assert_eq!(wei::max_cost(&value, 2, &max), Err(WeiError::Overflow));
+        assert_eq!(
+            wei::max_cost(&max, 1, &Wei(U256::one())),
+            Err(WeiError::Overflow)
+        );
     }
 }
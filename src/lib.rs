@@ -1,5 +1,15 @@
+// Explicit `return` is the house style throughout the crate.
+#![allow(clippy::needless_return)]
+
+pub mod asn1;
 pub mod base16;
-pub mod bytes;
+pub(crate) mod bytes;
+pub mod cbor;
+pub mod conformance;
 pub mod crypto;
+pub mod modctx;
+#[cfg(feature = "test-utils")]
+pub mod rng;
 pub mod secp256k1;
 pub mod u256;
+pub mod vdf;
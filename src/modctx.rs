@@ -0,0 +1,363 @@
+//! Modular arithmetic for a fixed odd modulus, with the Montgomery constants
+//! computed once in `ModCtx::new` and reused by every operation.
+//!
+//! Values go in and come out as plain residues; the Montgomery form only
+//! exists inside each method.
+
+use crate::u256::U256;
+
+/// Candidates tried for a non-residue in `tonelli_shanks` before giving up.
+const NON_RESIDUE_SEARCH: u64 = 1000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ModCtxError {
+    ZeroModulus,
+    /// Every residue mod 1 is 0.
+    ModulusOne,
+    /// Montgomery reduction needs gcd(p, 2^256) = 1.
+    EvenModulus,
+    /// Only from `new_prime`.
+    NotPrime,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModCtx {
+    p: U256,
+    /// -p^-1 mod 2^256
+    p_inv: U256,
+    /// R mod p, the Montgomery form of 1, R = 2^256
+    r1: U256,
+    /// R^2 mod p, to convert into Montgomery form
+    r2: U256,
+}
+
+impl ModCtx {
+    pub fn new(p: U256) -> Result<ModCtx, ModCtxError> {
+        if p.is_zero() {
+            return Err(ModCtxError::ZeroModulus);
+        }
+        if p.is_one() {
+            return Err(ModCtxError::ModulusOne);
+        }
+        if p.is_even() {
+            return Err(ModCtxError::EvenModulus);
+        }
+
+        // Newton's iteration for p^-1 mod 2^256. p * p = 1 (mod 8) for odd
+        // p, so p starts correct to 3 bits and each step doubles that.
+        let mut inv = p;
+        for _ in 0..7 {
+            let two_minus = U256::new(2).overflowing_sub(&p.wrapping_mul(&inv)).0;
+            inv = inv.wrapping_mul(&two_minus);
+        }
+
+        // R mod p = (2^256 - 1) mod p + 1, reduced
+        let r1 = U256::MAX.div_rem(&p).1.add_mod(&U256::ONE, &p);
+        let r2 = r1.mul_mod(&r1, &p);
+
+        return Ok(ModCtx {
+            p,
+            p_inv: U256::ZERO.overflowing_sub(&inv).0,
+            r1,
+            r2,
+        });
+    }
+
+    /// `new`, but also rejects p unless it passes 32 rounds of Miller-Rabin.
+    /// `inv` works for any odd modulus, `sqrt` needs this.
+    pub fn new_prime(p: U256) -> Result<ModCtx, ModCtxError> {
+        if !p.is_probable_prime(32) {
+            if p.is_zero() {
+                return Err(ModCtxError::ZeroModulus);
+            }
+            return Err(ModCtxError::NotPrime);
+        }
+        return Self::new(p);
+    }
+
+    pub fn modulus(&self) -> U256 {
+        return self.p;
+    }
+
+    pub fn add(&self, a: &U256, b: &U256) -> U256 {
+        return a.add_mod(b, &self.modulus());
+    }
+
+    pub fn sub(&self, a: &U256, b: &U256) -> U256 {
+        return a.sub_mod(b, &self.modulus());
+    }
+
+    /// REDC(aR * b) = ab
+    pub fn mul(&self, a: &U256, b: &U256) -> U256 {
+        let a = self.to_montgomery(a);
+        let (hi, lo) = a.full_mul(&b.div_rem(&self.p).1);
+        return self.redc(hi, lo);
+    }
+
+    /// Square-and-multiply, entirely in Montgomery form.
+    pub fn exp(&self, a: &U256, e: &U256) -> U256 {
+        let base = self.to_montgomery(a);
+        let mut acc = self.r1;
+        for i in (0..e.bit_len()).rev() {
+            acc = self.montgomery_mul(&acc, &acc);
+            if e.bit(i) {
+                acc = self.montgomery_mul(&acc, &base);
+            }
+        }
+
+        return self.redc(U256::ZERO, acc);
+    }
+
+    /// `U256::inv_mod`, so p need not be prime. None if gcd(a, p) != 1.
+    pub fn inv(&self, a: &U256) -> Option<U256> {
+        return a.inv_mod(&self.modulus());
+    }
+
+    /// The smaller of the two square roots of a, or None if a is not a
+    /// quadratic residue. The other root is p - r. Meant for prime p, see
+    /// `new_prime`; for composite p it may miss roots and return None, but
+    /// any root it does return is checked.
+    pub fn sqrt(&self, a: &U256) -> Option<U256> {
+        let r = self.tonelli_shanks(a)?;
+        return Some(r.min(r.neg_mod(&self.modulus())));
+    }
+
+    /// Either square root of a.
+    ///
+    /// Write p - 1 = q * 2^s with q odd. For p = 3 (mod 4), s = 1 and the
+    /// root is simply a^((p + 1) / 4).
+    fn tonelli_shanks(&self, a: &U256) -> Option<U256> {
+        let a = a.div_rem(&self.p).1;
+        if a.is_zero() {
+            return Some(a);
+        }
+        if !self.is_quadratic_residue(&a) {
+            return None;
+        }
+
+        // p is odd so p - 1 doesn't wrap. p + 1 wraps only for the
+        // composite 2^256 - 1, and the check at the end catches that.
+        let p_minus_1 = self.p.overflowing_sub(&U256::ONE).0;
+        let s = p_minus_1.trailing_zeros();
+        let q = p_minus_1 >> s;
+        if s == 1 {
+            let e = self.p.overflowing_add(&U256::ONE).0 >> 2;
+            return self.checked_root(&a, self.exp(&a, &e));
+        }
+
+        // Any non-residue z works; half of all residues are one, so for a
+        // prime p the search ends long before the cap
+        let mut z = U256::new(2);
+        while self.is_quadratic_residue(&z) {
+            if z >= U256::new(NON_RESIDUE_SEARCH) {
+                return None;
+            }
+            z = z.overflowing_add(&U256::ONE).0;
+        }
+
+        let mut m = s;
+        let mut c = self.exp(&z, &q);
+        let mut t = self.exp(&a, &q);
+        let mut r = self.exp(&a, &(q.overflowing_add(&U256::ONE).0 >> 1));
+        while !t.is_one() {
+            // Least i with t^(2^i) = 1, always < m for prime p
+            let mut i = 0;
+            let mut t2i = t;
+            while !t2i.is_one() {
+                t2i = self.mul(&t2i, &t2i);
+                i += 1;
+                if i == m {
+                    return None;
+                }
+            }
+
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = self.mul(&b, &b);
+            }
+            m = i;
+            c = self.mul(&b, &b);
+            t = self.mul(&t, &c);
+            r = self.mul(&r, &b);
+        }
+
+        return self.checked_root(&a, r);
+    }
+
+    /// r if r^2 = a, which always holds for prime p.
+    fn checked_root(&self, a: &U256, r: U256) -> Option<U256> {
+        if self.mul(&r, &r) != *a {
+            return None;
+        }
+        return Some(r);
+    }
+
+    /// Euler's criterion, a^((p - 1) / 2) = 1.
+    fn is_quadratic_residue(&self, a: &U256) -> bool {
+        // (p - 1) / 2, p is odd
+        let e = self.p >> 1;
+        return self.exp(a, &e).is_one();
+    }
+
+    fn to_montgomery(&self, a: &U256) -> U256 {
+        let (hi, lo) = a.div_rem(&self.p).1.full_mul(&self.r2);
+        return self.redc(hi, lo);
+    }
+
+    fn montgomery_mul(&self, a: &U256, b: &U256) -> U256 {
+        let (hi, lo) = a.full_mul(b);
+        return self.redc(hi, lo);
+    }
+
+    /// Montgomery reduction, t * R^-1 mod p for t < pR:
+    ///
+    /// m = (t mod R) * p_inv mod R
+    /// u = (t + m * p) / R, exact since t + m * p = 0 (mod R)
+    ///
+    /// u < 2p, so at most one subtraction. t + m * p can carry out of 512
+    /// bits, in which case u is really high + 2^256 and the wrapping
+    /// subtraction still gives the right answer.
+    fn redc(&self, hi: U256, lo: U256) -> U256 {
+        let m = lo.wrapping_mul(&self.p_inv);
+        let (mp_hi, mp_lo) = m.full_mul(&self.p);
+
+        // The low half of the sum is zero, only its carry matters
+        let low_carry = lo.overflowing_add(&mp_lo).1;
+        let (u, c1) = hi.overflowing_add(&mp_hi);
+        let (u, c2) = u.overflowing_add(&U256::new(low_carry as u64));
+        if c1 || c2 || u >= self.p {
+            return u.overflowing_sub(&self.p).0;
+        }
+        return u;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modctx::*;
+    use crate::rng::TestRng;
+    use crate::secp256k1::SECP256K1;
+    use rand::RngCore;
+    use std::str::FromStr;
+
+    fn primes() -> [U256; 4] {
+        return [
+            SECP256K1::p(),
+            SECP256K1::n(),
+            // BLS12-381 scalar field, p - 1 divisible by 2^32
+            U256::from_str("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+                .unwrap(),
+            U256::new(101),
+        ];
+    }
+
+    fn random_u256(rng: &mut TestRng) -> U256 {
+        let mut bytes = [0; 32];
+        rng.fill_bytes(&mut bytes);
+        return U256::from_be_bytes(&bytes);
+    }
+
+    #[test]
+    fn modctx_matches_u256() {
+        let mut rng = TestRng::new(237);
+        for p in primes() {
+            let ctx = ModCtx::new(p).unwrap();
+            for _ in 0..20 {
+                let a = random_u256(&mut rng);
+                let b = random_u256(&mut rng);
+                let e = U256::from_be_bytes(&[rng.next_u32() as u8, rng.next_u32() as u8]);
+
+                assert_eq!(ctx.add(&a, &b), a.add_mod(&b, &p));
+                assert_eq!(ctx.sub(&a, &b), a.sub_mod(&b, &p));
+                assert_eq!(ctx.mul(&a, &b), a.mul_mod(&b, &p));
+                assert_eq!(ctx.exp(&a, &e), a.exp_mod(&e, &p));
+
+                let inv = ctx.inv(&b).unwrap();
+                assert_eq!(ctx.mul(&b, &inv), U256::one());
+            }
+
+            let zero = U256::zero();
+            let one = U256::one();
+            assert_eq!(ctx.exp(&zero, &zero), one);
+            assert_eq!(ctx.mul(&p, &one), zero);
+            assert_eq!(ctx.inv(&zero), None);
+        }
+    }
+
+    #[test]
+    fn modctx_sqrt_composite() {
+        // These used to loop forever, or underflow for 1105
+        for (p, a) in [(21, 8), (25, 7), (561, 2)] {
+            let ctx = ModCtx::new(U256::new(p)).unwrap();
+            assert_eq!(ctx.sqrt(&U256::new(a)), None, "{} mod {}", a, p);
+        }
+
+        // Whatever comes back is a root
+        for p in [21, 25, 561, 1105] {
+            let ctx = ModCtx::new(U256::new(p)).unwrap();
+            for a in 0..p {
+                let a = U256::new(a);
+                if let Some(r) = ctx.sqrt(&a) {
+                    assert_eq!(ctx.mul(&r, &r), a, "{} mod {}", a, p);
+                }
+            }
+        }
+        assert_eq!(U256::new(7).sqrt_mod(&U256::new(25)), None);
+    }
+
+    #[test]
+    fn modctx_sqrt() {
+        let mut rng = TestRng::new(2370);
+        for p in primes() {
+            let ctx = ModCtx::new(p).unwrap();
+            let (mut residues, mut non_residues) = (0, 0);
+            for _ in 0..20 {
+                let a = random_u256(&mut rng);
+                let square = ctx.mul(&a, &a);
+                let r = ctx.sqrt(&square).unwrap();
+                assert!(r == a.div_rem(&p).1 || ctx.add(&r, &a) == U256::zero());
+
+                match ctx.sqrt(&a) {
+                    Some(r) => {
+                        assert_eq!(ctx.mul(&r, &r), a.div_rem(&p).1);
+                        residues += 1;
+                    }
+                    None => non_residues += 1,
+                }
+            }
+            assert!(residues > 0 && non_residues > 0);
+            assert_eq!(ctx.sqrt(&U256::zero()), Some(U256::zero()));
+        }
+    }
+
+    #[test]
+    fn modctx_errors() {
+        assert_eq!(
+            ModCtx::new(U256::zero()).err(),
+            Some(ModCtxError::ZeroModulus)
+        );
+        assert_eq!(
+            ModCtx::new(U256::one()).err(),
+            Some(ModCtxError::ModulusOne)
+        );
+        assert_eq!(
+            ModCtx::new(U256::new(100)).err(),
+            Some(ModCtxError::EvenModulus)
+        );
+        assert_eq!(
+            ModCtx::new_prime(U256::zero()).err(),
+            Some(ModCtxError::ZeroModulus)
+        );
+        assert_eq!(
+            ModCtx::new_prime(U256::new(91)).err(),
+            Some(ModCtxError::NotPrime)
+        );
+
+        assert!(ModCtx::new_prime(U256::new(101)).is_ok());
+
+        // Odd composites are fine without the primality check
+        let ctx = ModCtx::new(U256::new(91)).unwrap();
+        assert_eq!(ctx.inv(&U256::new(7)), None);
+        assert_eq!(ctx.inv(&U256::new(2)), Some(U256::new(46)));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::u256::U256;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// ChaCha20-based RNG seeded from a `u64`, so tests that need randomness
+/// (keys, nonces) are reproducible. Only for tests, never seed real keys
+/// with this.
+#[derive(Debug, Clone)]
+pub struct TestRng(ChaCha20Rng);
+
+impl TestRng {
+    pub fn new(seed: u64) -> Self {
+        return TestRng(ChaCha20Rng::seed_from_u64(seed));
+    }
+
+    /// A value biased towards where arithmetic bugs hide, in equal parts:
+    /// uniform, bit-sparse, within 2^16 of `modulus` (either side, wrapping),
+    /// within 2^16 of a limb boundary 2^64k, and below 2^16. Not reduced.
+    pub fn interesting_u256(&mut self, modulus: &U256) -> U256 {
+        let small = U256::new(self.next_u64() & 0xffff);
+        let below = self.next_u32() & 1 == 0;
+        let near = |v: U256| {
+            let r = if below {
+                v.overflowing_sub(&small)
+            } else {
+                v.overflowing_add(&small)
+            };
+            return r.0;
+        };
+
+        match self.next_u32() % 5 {
+            0 => return U256::random(self),
+            1 => {
+                let mut limbs = [0; 4];
+                for _ in 0..self.next_u32() % 8 {
+                    let bit = self.next_u32() % 256;
+                    limbs[(bit / 64) as usize] |= 1 << (bit % 64);
+                }
+                return U256::from_limbs(limbs);
+            }
+            2 => return near(*modulus),
+            3 => return near(U256::one() << (64 * (self.next_u32() % 4))),
+            _ => return small,
+        }
+    }
+}
+
+impl RngCore for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        return self.0.next_u32();
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        return self.0.next_u64();
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        return self.0.try_fill_bytes(dest);
+    }
+}
+
+impl CryptoRng for TestRng {}
+
+#[cfg(test)]
+mod tests {
+    use crate::rng::TestRng;
+    use crate::secp256k1::SECP256K1;
+    use crate::u256::U256;
+    use rand::RngCore;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = [0; 32];
+        let mut b = [0; 32];
+        TestRng::new(7).fill_bytes(&mut a);
+        TestRng::new(7).fill_bytes(&mut b);
+        assert_eq!(a, b);
+
+        TestRng::new(8).fill_bytes(&mut b);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn interesting_u256_covers_edges() {
+        let mut rng = TestRng::new(278);
+        let p = SECP256K1::p();
+        let (mut sparse, mut near_p, mut above_p, mut small) = (0, 0, 0, 0);
+        for _ in 0..1000 {
+            let u = rng.interesting_u256(&p);
+            let ones: u32 = u.as_limbs().iter().map(|l| l.count_ones()).sum();
+            sparse += (ones < 8) as u32;
+            let diff = u.max(p).overflowing_sub(&u.min(p)).0;
+            near_p += (diff < U256::new(0x10000)) as u32;
+            above_p += (u > p) as u32;
+            small += (u.bit_len() <= 16) as u32;
+        }
+        for count in [sparse, near_p, above_p, small] {
+            assert!(count > 50, "{:?}", (sparse, near_p, above_p, small));
+        }
+    }
+}
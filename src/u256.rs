@@ -1,57 +1,588 @@
+use crate::base16;
 use crate::bytes;
-use hex;
-use primitive_types::U256 as PU256;
+use crate::modctx::ModCtx;
+use primitive_types::{U256 as PU256, U512};
+use rand::{CryptoRng, RngCore};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Shl, ShlAssign, Shr, ShrAssign};
 use std::str::FromStr;
 
-#[derive(Debug, Clone)]
+const SMALL_PRIMES: [u64; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// A 256-bit unsigned integer, zero by `Default`. Everything outside this
+/// file goes through `as_limbs` / `from_limbs` and the integer methods
+/// rather than the primitive-types value, so the representation can change.
+/// It is `Copy`, so secrets held in one are copied freely and `zeroize`
+/// clears only the value it is called on.
+#[derive(Debug, Clone, Copy, Default)]
 pub struct U256 {
-    pub v: PU256,
+    v: PU256,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum U256ParseError {
+    /// Nothing left to parse, after the 0x prefix for hex.
+    Empty,
+    /// `position` counts characters from the start of the input, prefix
+    /// included.
+    InvalidCharacter { position: usize, character: char },
+    /// More than 64 hex digits.
+    TooLong { len: usize },
+    /// A decimal value above 2^256 - 1.
+    Overflow,
+}
+
+impl fmt::Display for U256ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => return write!(f, "empty number"),
+            Self::InvalidCharacter {
+                position,
+                character,
+            } => {
+                return write!(
+                    f,
+                    "invalid character {:?} at position {}",
+                    character, position
+                )
+            }
+            Self::TooLong { len } => return write!(f, "{} hex digits, at most 64 fit", len),
+            Self::Overflow => return write!(f, "value does not fit in 256 bits"),
+        }
+    }
+}
+
+impl std::error::Error for U256ParseError {}
+
+/// An output buffer of the wrong size.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LengthError {
+    pub expected: usize,
+    pub got: usize,
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "expected {} bytes, got {}", self.expected, self.got);
+    }
+}
+
+impl std::error::Error for LengthError {}
+
+/// A non-canonical RLP integer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RlpIntError {
+    /// RLP integers are minimal, zero is the empty string.
+    LeadingZero,
+    TooLong {
+        max: usize,
+        got: usize,
+    },
+}
+
+impl fmt::Display for RlpIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Self::LeadingZero => write!(f, "RLP integer with a leading zero byte"),
+            Self::TooLong { max, got } => {
+                write!(f, "RLP integer of {} bytes, at most {} allowed", got, max)
+            }
+        };
+    }
+}
+
+impl std::error::Error for RlpIntError {}
+
+/// `U256::to_rlp_bytes` for a u64, without going through 256 bits.
+pub fn u64_to_rlp_bytes(n: u64) -> Vec<u8> {
+    return rlp_trim(&n.to_be_bytes());
+}
+
+pub fn u64_from_rlp_bytes(bs: &[u8]) -> Result<u64, RlpIntError> {
+    check_rlp_int(bs, 8)?;
+    let mut be = [0; 8];
+    be[8 - bs.len()..].copy_from_slice(bs);
+    return Ok(u64::from_be_bytes(be));
+}
+
+pub fn u128_to_rlp_bytes(n: u128) -> Vec<u8> {
+    return rlp_trim(&n.to_be_bytes());
+}
+
+pub fn u128_from_rlp_bytes(bs: &[u8]) -> Result<u128, RlpIntError> {
+    check_rlp_int(bs, 16)?;
+    let mut be = [0; 16];
+    be[16 - bs.len()..].copy_from_slice(bs);
+    return Ok(u128::from_be_bytes(be));
+}
+
+fn rlp_trim(be: &[u8]) -> Vec<u8> {
+    let zeros = be.iter().take_while(|&&b| b == 0).count();
+    return be[zeros..].to_vec();
+}
+
+fn check_rlp_int(bs: &[u8], max: usize) -> Result<(), RlpIntError> {
+    if bs.len() > max {
+        return Err(RlpIntError::TooLong { max, got: bs.len() });
+    }
+    if bs.first() == Some(&0) {
+        return Err(RlpIntError::LeadingZero);
+    }
+    return Ok(());
+}
+
+/// From the `try_` modular operations.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ArithError {
+    ZeroModulus,
+    /// The divisor shares a factor with the modulus.
+    NonInvertible,
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct U256ParseError;
+pub enum U256Error {
+    /// The Jacobi symbol is only defined for odd moduli.
+    EvenModulus,
+    /// Too large for the integer type being converted into.
+    Overflow,
+}
+
+/// A Bezout coefficient from `extended_gcd`, as sign and magnitude. The
+/// magnitude never exceeds the larger input so it always fits in a U256.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BezoutCoefficient {
+    pub negative: bool,
+    pub magnitude: U256,
+}
 
 impl FromStr for U256 {
     type Err = U256ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match PU256::from_str_radix(s, 16) {
-            Ok(n) => return Ok(Self { v: n }),
-            Err(_) => return Err(U256ParseError),
+        let digits = hex_digits(s)?;
+        let v = PU256::from_str_radix(digits, 16).expect("validated-hex");
+        return Ok(Self { v });
+    }
+}
+
+/// The digits of a hex number, checked to be 1 to 64 hex characters after
+/// trimming whitespace and an optional 0x or 0X prefix.
+fn hex_digits(s: &str) -> Result<&str, U256ParseError> {
+    let trimmed = s.trim_start();
+    let digits = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    let skipped = s.len() - digits.len();
+    let digits = digits.trim_end();
+    if digits.is_empty() {
+        return Err(U256ParseError::Empty);
+    }
+
+    if let Some((i, c)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(U256ParseError::InvalidCharacter {
+            position: s[..skipped + i].chars().count(),
+            character: c,
+        });
+    }
+    if digits.len() > 64 {
+        return Err(U256ParseError::TooLong { len: digits.len() });
+    }
+
+    return Ok(digits);
+}
+
+/// Big-endian, like `from_be_bytes`, but a slice over 32 bytes is an error
+/// rather than a panic.
+impl TryFrom<&[u8]> for U256 {
+    type Error = LengthError;
+    fn try_from(bs: &[u8]) -> Result<Self, Self::Error> {
+        if bs.len() > 32 {
+            return Err(LengthError {
+                expected: 32,
+                got: bs.len(),
+            });
         }
+        return Ok(Self::from_be_bytes(bs));
     }
 }
 
-impl ToString for U256 {
-    fn to_string(&self) -> String {
-        let mut bytes = [0; 32];
-        self.v.to_big_endian(&mut bytes);
-        return hex::encode(bytes);
+impl From<[u8; 32]> for U256 {
+    fn from(bs: [u8; 32]) -> Self {
+        return Self {
+            v: PU256::from_big_endian(&bs),
+        };
+    }
+}
+
+impl From<u8> for U256 {
+    fn from(n: u8) -> Self {
+        return Self { v: PU256::from(n) };
+    }
+}
+
+impl From<u32> for U256 {
+    fn from(n: u32) -> Self {
+        return Self { v: PU256::from(n) };
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(n: u64) -> Self {
+        return Self { v: PU256::from(n) };
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(n: u128) -> Self {
+        return Self { v: PU256::from(n) };
+    }
+}
+
+impl TryFrom<U256> for u64 {
+    type Error = U256Error;
+    fn try_from(n: U256) -> Result<Self, Self::Error> {
+        if n.v.bits() > 64 {
+            return Err(U256Error::Overflow);
+        }
+        return Ok(n.v.low_u64());
+    }
+}
+
+impl TryFrom<U256> for u128 {
+    type Error = U256Error;
+    fn try_from(n: U256) -> Result<Self, Self::Error> {
+        if n.v.bits() > 128 {
+            return Err(U256Error::Overflow);
+        }
+        return Ok(n.v.low_u128());
+    }
+}
+
+/// The canonical form: 64 lowercase hex digits, zero padded, no prefix.
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = [0; 64];
+        self.write_hex_fixed(&mut out);
+        return f.write_str(std::str::from_utf8(&out).expect("ascii-hex"));
+    }
+}
+
+/// Minimal hex without leading zeros, `{:#x}` adds 0x.
+impl fmt::LowerHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = format!("{:x}", self.v);
+        return f.pad_integral(true, "0x", &digits);
+    }
+}
+
+impl fmt::UpperHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = format!("{:x}", self.v).to_uppercase();
+        return f.pad_integral(true, "0x", &digits);
     }
 }
 
 impl U256 {
-    /**
+    /*
      * UTILITIES
      */
+    #[deprecated(note = "use from_be_bytes, or TryFrom<&[u8]> for untrusted input")]
     pub fn from_bytes(bs: &[u8]) -> Self {
+        return Self::from_be_bytes(bs);
+    }
+    #[deprecated(note = "use to_be_bytes, or to_bytes_into for a slice")]
+    pub fn to_bytes(&self, r: &mut [u8]) {
+        self.v.to_big_endian(r);
+    }
+
+    /// Up to 32 bytes, shorter input has implicit leading zeros. Panics on
+    /// anything longer, use `TryFrom<&[u8]>` for untrusted input.
+    pub fn from_be_bytes(bs: &[u8]) -> Self {
         assert!(bs.len() <= 32, "big-endian");
 
         return Self {
             v: PU256::from_big_endian(bs),
         };
     }
-    pub fn to_bytes(&self, r: &mut [u8]) {
-        self.v.to_big_endian(r);
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut r = [0; 32];
+        self.v.to_big_endian(&mut r);
+        return r;
+    }
+    /// Up to 32 bytes, least significant first, so shorter input has
+    /// implicit trailing zeros.
+    pub fn from_le_bytes(bs: &[u8]) -> Result<Self, LengthError> {
+        if bs.len() > 32 {
+            return Err(LengthError {
+                expected: 32,
+                got: bs.len(),
+            });
+        }
+        return Ok(Self {
+            v: PU256::from_little_endian(bs),
+        });
+    }
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        let mut r = [0; 32];
+        self.v.to_little_endian(&mut r);
+        return r;
+    }
+
+    /// Big-endian into `out`, which must be exactly 32 bytes.
+    pub fn to_bytes_into(&self, out: &mut [u8]) -> Result<(), LengthError> {
+        if out.len() != 32 {
+            return Err(LengthError {
+                expected: 32,
+                got: out.len(),
+            });
+        }
+        self.v.to_big_endian(out);
+        return Ok(());
+    }
+
+    /// Minimal big-endian, as RLP encodes integers: no leading zeros, and
+    /// empty for zero.
+    pub fn to_rlp_bytes(&self) -> Vec<u8> {
+        return rlp_trim(&self.to_be_bytes());
+    }
+    /// Inverse of `to_rlp_bytes`, rejecting any non-minimal encoding.
+    pub fn from_rlp_bytes(bs: &[u8]) -> Result<Self, RlpIntError> {
+        check_rlp_int(bs, 32)?;
+        return Ok(Self::from_be_bytes(bs));
+    }
+
+    pub const ZERO: U256 = U256::from_limbs([0; 4]);
+    pub const ONE: U256 = U256::from_limbs([1, 0, 0, 0]);
+    pub const MAX: U256 = U256::from_limbs([u64::MAX; 4]);
+
+    /// A small value, without parsing.
+    pub const fn new(n: u64) -> Self {
+        return Self::from_limbs([n, 0, 0, 0]);
+    }
+
+    /// Least significant limb first, the primitive-types layout.
+    pub const fn from_limbs(limbs: [u64; 4]) -> Self {
+        return Self { v: PU256(limbs) };
+    }
+    pub fn as_limbs(&self) -> &[u64; 4] {
+        return &self.v.0;
+    }
+
+    /// Overwrites this value with zeros through `bytes::wipe`. Only this
+    /// one: `U256` is `Copy`, and every copy made before, including any
+    /// passed by value, keeps the secret. Besides `U256`, only the buffers
+    /// inside `scalar_mul` are wiped; hex `String`s are not.
+    pub fn zeroize(&mut self) {
+        let limbs = &mut self.v.0;
+        // SAFETY: the 32 bytes of the four limbs, exclusively borrowed, and
+        // u8 has no alignment requirement
+        let bytes = unsafe { std::slice::from_raw_parts_mut(limbs.as_mut_ptr() as *mut u8, 32) };
+        bytes::wipe(bytes);
+    }
+
+    /// Uniform over all 256-bit values.
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        let mut bytes = [0; 32];
+        rng.fill_bytes(&mut bytes);
+        return Self::from_be_bytes(&bytes);
+    }
+    /// Uniform in [0, bound), by drawing `bound.bit_len()` bits until the
+    /// value is below bound, which takes fewer than two draws on average.
+    /// With the curve order as bound this is how to generate a private key.
+    /// Panics if bound is zero.
+    pub fn random_below<R: RngCore + CryptoRng>(rng: &mut R, bound: &U256) -> Self {
+        assert!(!bound.is_zero(), "nonzero-bound");
+
+        let shift = 256 - bound.bit_len() as u32;
+        loop {
+            let r = Self::random(rng) >> shift;
+            if r < *bound {
+                return r;
+            }
+        }
+    }
+
+    /// `from_hex_str`, the name used for constants.
+    pub fn from_hex(s: &str) -> Result<Self, U256ParseError> {
+        return Self::from_hex_str(s);
+    }
+
+    /// Parses up to 64 hex digits straight into the limbs, accepting the
+    /// same input as `from_str`. Unlike `from_str` this never allocates.
+    pub fn from_hex_str(s: &str) -> Result<Self, U256ParseError> {
+        let digits = hex_digits(s)?.as_bytes();
+
+        // Least significant digit first, 16 per limb
+        let mut limbs = [0u64; 4];
+        for (i, c) in digits.iter().rev().enumerate() {
+            let nibble = (*c as char).to_digit(16).expect("validated-hex");
+            limbs[i / 16] |= (nibble as u64) << (4 * (i % 16));
+        }
+
+        return Ok(Self { v: PU256(limbs) });
+    }
+
+    /// Same output as `Display`, written into `out` instead of a new
+    /// `String`.
+    pub fn write_hex_fixed(&self, out: &mut [u8; 64]) {
+        base16::encode_bytes_into(&self.to_be_bytes(), out);
+    }
+
+    /// Parses a base-10 string. Leading zeros are fine, anything above
+    /// 2^256 - 1 is an error.
+    pub fn from_dec_str(s: &str) -> Result<Self, U256ParseError> {
+        if s.is_empty() {
+            return Err(U256ParseError::Empty);
+        }
+
+        let ten = PU256::from(10);
+        let mut v = PU256::zero();
+        for (position, character) in s.chars().enumerate() {
+            let digit = character
+                .to_digit(10)
+                .ok_or(U256ParseError::InvalidCharacter {
+                    position,
+                    character,
+                })?;
+            v = v
+                .checked_mul(ten)
+                .and_then(|v| v.checked_add(PU256::from(digit)))
+                .ok_or(U256ParseError::Overflow)?;
+        }
+
+        return Ok(Self { v });
+    }
+
+    /// Base-10 form without leading zeros, by repeated divmod by 10.
+    pub fn to_dec_string(&self) -> String {
+        let ten = PU256::from(10);
+        let mut digits = Vec::new();
+        let mut v = self.v;
+        loop {
+            let (q, r) = v.div_mod(ten);
+            digits.push(b'0' + r.low_u32() as u8);
+            v = q;
+            if v.is_zero() {
+                break;
+            }
+        }
+        digits.reverse();
+
+        return String::from_utf8(digits).expect("ascii-digits");
+    }
+
+    /// Index 0 is the least significant bit. Past 255 is always false.
+    pub fn bit(&self, index: usize) -> bool {
+        return index < 256 && self.v.bit(index);
+    }
+    /// Position of the highest set bit plus one, 0 for zero.
+    pub fn bit_len(&self) -> usize {
+        return self.v.bits();
+    }
+    /// The low bit, from the least significant limb alone.
+    pub fn parity(&self) -> u8 {
+        return (self.v.0[0] & 1) as u8;
+    }
+    pub fn is_even(&self) -> bool {
+        return self.parity() == 0;
+    }
+    pub fn is_odd(&self) -> bool {
+        return self.parity() == 1;
+    }
+
+    /// Like the integer types, shifts by `shift mod 256`.
+    pub fn wrapping_shl(&self, shift: u32) -> Self {
+        return *self << (shift & 255);
+    }
+    pub fn wrapping_shr(&self, shift: u32) -> Self {
+        return *self >> (shift & 255);
+    }
+    /// 256 for zero, so `bit_len() + leading_zeros() == 256` always.
+    pub fn leading_zeros(&self) -> u32 {
+        return self.v.leading_zeros();
+    }
+    /// 256 for zero.
+    pub fn trailing_zeros(&self) -> u32 {
+        return self.v.trailing_zeros();
+    }
+
+    /// Limb comparisons, no constant is built.
+    pub fn is_zero(&self) -> bool {
+        return self.v.0 == [0, 0, 0, 0];
+    }
+    pub fn is_one(&self) -> bool {
+        return self.v.0 == [1, 0, 0, 0];
     }
 
     pub fn zero() -> Self {
-        return Self::from_str("0x0").unwrap();
+        return Self::ZERO;
     }
     pub fn one() -> Self {
-        return Self::from_str("0x1").unwrap();
+        return Self::ONE;
+    }
+
+    /*
+     * INTEGER ARITHMETIC
+     */
+
+    /// The wrapped result and whether it wrapped, like the integer types.
+    pub fn overflowing_add(&self, b: &Self) -> (Self, bool) {
+        let (v, over) = self.v.overflowing_add(b.v);
+        return (Self { v }, over);
+    }
+    pub fn overflowing_sub(&self, b: &Self) -> (Self, bool) {
+        let (v, over) = self.v.overflowing_sub(b.v);
+        return (Self { v }, over);
+    }
+    /// The low 256 bits of the product, a·b mod 2^256.
+    pub fn wrapping_mul(&self, b: &Self) -> Self {
+        return Self {
+            v: self.v.overflowing_mul(b.v).0,
+        };
+    }
+
+    /// None on overflow, or underflow for `checked_sub`.
+    pub fn checked_add(&self, b: &Self) -> Option<Self> {
+        return self.v.checked_add(b.v).map(|v| Self { v });
+    }
+    pub fn checked_sub(&self, b: &Self) -> Option<Self> {
+        return self.v.checked_sub(b.v).map(|v| Self { v });
+    }
+    pub fn checked_mul(&self, b: &Self) -> Option<Self> {
+        return self.v.checked_mul(b.v).map(|v| Self { v });
+    }
+
+    /// Quotient and remainder. Panics if b is zero, like `/`.
+    pub fn div_rem(&self, b: &Self) -> (Self, Self) {
+        assert!(!b.is_zero(), "division by zero");
+        let (q, r) = self.v.div_mod(b.v);
+        return (Self { v: q }, Self { v: r });
     }
 
-    /**
+    /// The full 512-bit product as (high, low) halves, the order
+    /// `rem_wide` and `reduce_secp256k1` take them in.
+    pub fn full_mul(&self, b: &Self) -> (Self, Self) {
+        let t = self.v.full_mul(b.v);
+        return (
+            Self::from_limbs([t.0[4], t.0[5], t.0[6], t.0[7]]),
+            Self::from_limbs([t.0[0], t.0[1], t.0[2], t.0[3]]),
+        );
+    }
+
+    /// (hi·2^256 + lo) mod m, a 512-bit division. Panics if m is zero.
+    pub fn rem_wide(hi: &Self, lo: &Self, m: &Self) -> Self {
+        assert!(!m.is_zero(), "division by zero");
+        let (hi, lo) = (hi.v.0, lo.v.0);
+        let t = U512([lo[0], lo[1], lo[2], lo[3], hi[0], hi[1], hi[2], hi[3]]);
+        return Self {
+            v: PU256::try_from(t % U512::from(m.v)).expect("reduced"),
+        };
+    }
+
+    /*
      * ARITHMETIC
      */
 
@@ -77,8 +608,11 @@ impl U256 {
     /// The truncated result will not be the correct modulo answer... it is
     /// missing segment x = U256.max - p + 1. So this must be added back.
     pub fn add_mod(&self, b: &Self, p: &Self) -> Self {
-        let x1 = self.v.checked_rem(p.v).expect("modulo");
-        let x2 = b.v.checked_rem(p.v).expect("modulo");
+        return self.try_add_mod(b, p).expect("modulo");
+    }
+    pub fn try_add_mod(&self, b: &Self, p: &Self) -> Result<Self, ArithError> {
+        let x1 = self.v.checked_rem(p.v).ok_or(ArithError::ZeroModulus)?;
+        let x2 = b.v.checked_rem(p.v).ok_or(ArithError::ZeroModulus)?;
 
         // Get truncated result if there is an overflow
         let (mut x3, over) = x1.overflowing_add(x2);
@@ -90,15 +624,15 @@ impl U256 {
                     PU256::MAX
                         .checked_sub(p.v)
                         .expect("sub")
-                        .checked_add(PU256::from_big_endian(&[1]))
+                        .checked_add(U256::from(1u8).v)
                         .expect("conversion"),
                 )
                 .expect("add");
         }
 
-        x3 = x3.checked_rem(p.v).expect("modulo");
+        x3 %= p.v;
 
-        return Self { v: x3 };
+        return Ok(Self { v: x3 });
     }
 
     /// a - b (mod p) = (a mod p - b mod p) mod p
@@ -109,111 +643,68 @@ impl U256 {
     /// To prevent underflow in case b > p:
     /// = (a mod p + ((p - (b mod p))) mod p)) mod p
     pub fn sub_mod(&self, b: &Self, p: &Self) -> Self {
-        let x1 = self.v.checked_rem(p.v).expect("modulo");
-        let x2 = b.v.checked_rem(p.v).expect("modulo");
-
-        return Self { v: x1 }.add_mod(&Self { v: (p.v - x2) }, p);
+        return self.try_sub_mod(b, p).expect("modulo");
     }
+    pub fn try_sub_mod(&self, b: &Self, p: &Self) -> Result<Self, ArithError> {
+        let x1 = self.v.checked_rem(p.v).ok_or(ArithError::ZeroModulus)?;
+        let x2 = b.v.checked_rem(p.v).ok_or(ArithError::ZeroModulus)?;
 
-    /// Uses Add-and-Double algorithm for O(log n) time complexity
-    /// Will define multiplication as repeated addition:
-    ///
-    /// 13 * 10 = 13 + 13 + ... + 13 + 13 (11 times)
-    ///
-    /// Algorithm would use these steps:
-    /// - 0  +  0 + 13 = 13
-    /// - 13 + 13      = 26
-    /// - 26 + 26 + 13 = 65
-    /// - 65 + 65 + 13 = 143
-    ///
-    /// The algorithm at each step either doubles the previous number, or
-    /// doubles the previous number and adds 13. To determine which to do, the
-    /// binary representation is required. 11 = 0b1011
-    ///
-    /// Iterate through the binary string from left to right. If the current bit
-    /// is 1, double and add 13. If the current bit is 0, only double.
-    ///
-    /// *1* - 0  +  0 + 13 = 13
-    /// *0* - 13 + 13      = 26
-    /// *1* - 26 + 26 + 13 = 65
-    /// *1* - 65 + 65 + 13 = 143
-    pub fn mul_mod(&self, b: &Self, p: &Self) -> Self {
-        let x1 = Self {
-            v: self.v.checked_rem(p.v).expect("modulo"),
-        };
-        let x2 = Self {
-            v: b.v.checked_rem(p.v).expect("modulo"),
-        };
-
-        let mut base = Self::zero();
-
-        let seq: Self;
-        let adder: Self;
+        return Self { v: x1 }.try_add_mod(&Self { v: (p.v - x2) }, p);
+    }
 
-        // Assume seq is the smaller of the two factors
-        if x1.v < x2.v {
-            seq = x1;
-            adder = x2;
-        } else {
-            seq = x2;
-            adder = x1;
+    /// -a (mod p) = (p - (a mod p)) mod p, so -0 is 0 rather than p.
+    pub fn neg_mod(&self, p: &Self) -> Self {
+        return self.try_neg_mod(p).expect("modulo");
+    }
+    pub fn try_neg_mod(&self, p: &Self) -> Result<Self, ArithError> {
+        let x = self.v.checked_rem(p.v).ok_or(ArithError::ZeroModulus)?;
+        if x.is_zero() {
+            return Ok(Self { v: x });
         }
 
-        let mut seq_bytes = [0; 32];
-        seq.to_bytes(&mut seq_bytes);
-
-        let mut seq_binaries: Vec<u8> = vec![];
-        bytes::bytes_to_binary(&seq_bytes, &mut seq_binaries);
+        return Ok(Self { v: p.v - x });
+    }
 
-        // Begin doubling after first 1 bit. Also add the `adder` for every 1
-        // bit. Repeated modular addition assures result remains on the finite
-        // field
-        let mut on = false;
-        for d in seq_binaries.into_iter() {
-            if on {
-                base = base.add_mod(&base, p);
-            }
-            if d > 0 {
-                on = true;
-                base = base.add_mod(&adder, p);
-            }
+    /// a·b mod p, through the full 512-bit product so nothing overflows.
+    pub fn mul_mod(&self, b: &Self, p: &Self) -> Self {
+        return self.try_mul_mod(b, p).expect("modulo");
+    }
+    pub fn try_mul_mod(&self, b: &Self, p: &Self) -> Result<Self, ArithError> {
+        if p.is_zero() {
+            return Err(ArithError::ZeroModulus);
         }
 
-        return base;
+        let (hi, lo) = self.full_mul(b);
+        return Ok(Self::rem_wide(&hi, &lo, p));
     }
 
-    /// Will use Square-and-Multiply algorithm for O(log n) time complexity
-    /// Similar to the multiplication algorithm above, but instead of repeated
-    /// addition, it will be repeated multiplication.
+    /// Square-and-multiply over the bits of e, most significant first, the
+    /// multiplicative version of the double-and-add in
+    /// `SECP256K1::scalar_mul`. O(log e) multiplications.
     pub fn exp_mod(&self, e: &Self, p: &Self) -> Self {
-        let seq = e;
+        return self.try_exp_mod(e, p).expect("modulo");
+    }
+    pub fn try_exp_mod(&self, e: &Self, p: &Self) -> Result<Self, ArithError> {
         let multiplier = U256 {
-            v: self.v.checked_rem(p.v).expect("modulo"),
+            v: self.v.checked_rem(p.v).ok_or(ArithError::ZeroModulus)?,
         };
 
-        let mut base = Self::one();
-
-        let mut seq_bytes = [0; 32];
-        seq.to_bytes(&mut seq_bytes);
-
-        let mut seq_binaries: Vec<u8> = vec![];
-        bytes::bytes_to_binary(&seq_bytes, &mut seq_binaries);
+        // 1 mod p, so that x^0 = 0 (mod 1)
+        let mut base = Self {
+            v: PU256::one() % p.v,
+        };
 
-        // Begin squaring after first 1 bit. Also add the `adder` for every 1
-        // bit. Repeated modular addition assures result remains on the finite
-        // field
-        let mut on = false;
-        for d in seq_binaries.into_iter() {
-            if on {
-                base = base.mul_mod(&base, p);
-            }
-            if d > 0 {
-                on = true;
+        // Square for every bit below the highest set one, and multiply in
+        // the base for every 1 bit. Repeated modular multiplication assures
+        // result remains on the finite field
+        for i in (0..e.bit_len()).rev() {
+            base = base.mul_mod(&base, p);
+            if e.bit(i) {
                 base = base.mul_mod(&multiplier, p);
             }
         }
 
-        return base;
+        return Ok(base);
     }
 
     /// (a / b) (mod p) = (a * b^-1) (mod p)
@@ -222,53 +713,474 @@ impl U256 {
     /// = (a * b^(p - 1) * b^-1) (mod p)
     /// = (a * b^(p - 2)) (mod p)
     /// = ((a mod p) * (b^(p - 2) mod p)) mod p
+    ///
+    /// Every residue mod 1 is 0. Panics on a zero modulus, like the rest of
+    /// the `_mod` family.
     pub fn div_mod(&self, b: &Self, p: &Self) -> Self {
-        assert!(p.v >= PU256::from_big_endian(&[2]));
-        return self.mul_mod(&b.exp_mod(&U256 { v: p.v - 2 }, p), p);
+        assert!(!p.is_zero(), "modulo");
+        if p.is_one() {
+            return Self::zero();
+        }
+        #[cfg(all(debug_assertions, feature = "debug-primality"))]
+        debug_assert!(p.is_probable_prime(8), "modulus is not prime");
+        let e = U256 {
+            v: p.v - U256::from(2u8).v,
+        };
+        return self.mul_mod(&b.exp_mod(&e, p), p);
     }
-}
 
-impl PartialEq for U256 {
-    fn eq(&self, other: &Self) -> bool {
-        return self.v == other.v;
+    /// (a / b) (mod p) through `inv_mod` rather than Fermat, so p need not be
+    /// prime. Unlike `div_mod`, b = 0 is an error instead of giving 0.
+    pub fn try_div_mod(&self, b: &Self, p: &Self) -> Result<Self, ArithError> {
+        if p.is_zero() {
+            return Err(ArithError::ZeroModulus);
+        }
+        if p.is_one() {
+            return Ok(Self::zero());
+        }
+        let inv = b.inv_mod(p).ok_or(ArithError::NonInvertible)?;
+        return self.try_mul_mod(&inv, p);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::u256::U256;
-    use std::str::FromStr;
+    /*
+     * NUMBER THEORY
+     */
 
-    #[test]
-    fn addition_case_1() {
-        let a = U256::from_str("0xBD").unwrap();
-        let b = U256::from_str("0x2B").unwrap();
-        let p = U256::from_str("0xB").unwrap();
+    /// Binary (Stein's) GCD. Only shifts and subtractions, no division:
+    ///
+    /// gcd(2a, 2b) = 2 gcd(a, b)
+    /// gcd(2a, b)  = gcd(a, b)        if b is odd
+    /// gcd(a, b)   = gcd(|a - b|, b)  if both are odd
+    ///
+    /// gcd(0, x) = x.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let mut a = self.v;
+        let mut b = other.v;
+        if a.is_zero() {
+            return Self { v: b };
+        }
+        if b.is_zero() {
+            return Self { v: a };
+        }
 
-        let r = a.add_mod(&b, &p);
+        // Common factors of two
+        let shift = (a | b).trailing_zeros();
+        a >>= a.trailing_zeros();
 
-        assert_eq!(
-            r.to_string(),
-            "0000000000000000000000000000000000000000000000000000000000000001"
-        );
+        loop {
+            b >>= b.trailing_zeros();
+            if a > b {
+                std::mem::swap(&mut a, &mut b);
+            }
+            b -= a;
+            if b.is_zero() {
+                return Self { v: a << shift };
+            }
+        }
     }
 
-    #[test]
-    fn addition_case_2() {
-        let a = U256::from_str("0xa167f055ff75c").unwrap();
-        let b = U256::from_str("0xacc457752e4ed").unwrap();
-        let p = U256::from_str("0xf9cd").unwrap();
-
-        let r = a.add_mod(&b, &p);
-
-        assert_eq!(
-            r.to_string(),
-            "0000000000000000000000000000000000000000000000000000000000006bb0"
-        );
+    /// Miller-Rabin with `rounds` random bases, after trial division by the
+    /// primes below 100. A composite passes with probability at most 4^-rounds.
+    pub fn is_probable_prime(&self, rounds: u32) -> bool {
+        return self.is_probable_prime_with_rng(rounds, &mut rand::thread_rng());
     }
 
-    #[test]
-    fn addition_case_3() {
+    /// `is_probable_prime` with the bases drawn from `rng`, so a seeded
+    /// `TestRng` gives a reproducible answer.
+    pub fn is_probable_prime_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rounds: u32,
+        rng: &mut R,
+    ) -> bool {
+        if let Some(r) = self.small_prime_check() {
+            return r;
+        }
+
+        let (d, s) = self.odd_part_of_predecessor();
+        let range = Self { v: self.v - 3 };
+        for _ in 0..rounds {
+            // Base in [2, n - 2]
+            let a = Self {
+                v: Self::random_below(rng, &range).v + 2,
+            };
+            if !self.miller_rabin_round(&a, &d, s) {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    /// Miller-Rabin with the first 13 primes (2 to 41) as bases, which is
+    /// known to have no strong pseudoprimes below 3317044064679887385961981.
+    /// The answer is exact in that range, `None` above it.
+    pub fn is_prime_deterministic(&self) -> Option<bool> {
+        let limit = Self {
+            v: PU256::from_dec_str("3317044064679887385961981").unwrap(),
+        };
+        if *self >= limit {
+            return None;
+        }
+        if let Some(r) = self.small_prime_check() {
+            return Some(r);
+        }
+
+        let (d, s) = self.odd_part_of_predecessor();
+        let r = SMALL_PRIMES[..13].iter().all(|a| {
+            let a = Self { v: PU256::from(*a) };
+            return self.miller_rabin_round(&a, &d, s);
+        });
+
+        return Some(r);
+    }
+
+    /// Decides primality by trial division when the value is small enough
+    /// (below 97^2) or has a small factor.
+    fn small_prime_check(&self) -> Option<bool> {
+        if self.v < PU256::from(2) {
+            return Some(false);
+        }
+        for p in SMALL_PRIMES {
+            let p = PU256::from(p);
+            if self.v == p {
+                return Some(true);
+            }
+            if (self.v % p).is_zero() {
+                return Some(false);
+            }
+        }
+        if self.v < PU256::from(97 * 97) {
+            return Some(true);
+        }
+
+        return None;
+    }
+
+    /// n - 1 = d * 2^s with d odd.
+    fn odd_part_of_predecessor(&self) -> (Self, u32) {
+        let n_1 = self.v - 1;
+        let s = n_1.trailing_zeros();
+        return (Self { v: n_1 >> s }, s);
+    }
+
+    /// One round with base a: n passes if a^d = 1 or a^(d 2^r) = -1 for
+    /// some r < s.
+    fn miller_rabin_round(&self, a: &Self, d: &Self, s: u32) -> bool {
+        let n_1 = Self { v: self.v - 1 };
+
+        let mut x = a.exp_mod(d, self);
+        if x.is_one() || x == n_1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = x.mul_mod(&x, self);
+            if x == n_1 {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    /// lcm(a, b) = a / gcd(a, b) * b, or `None` if that doesn't fit in 256
+    /// bits. lcm(0, x) = 0.
+    pub fn lcm(&self, other: &Self) -> Option<Self> {
+        if self.is_zero() || other.is_zero() {
+            return Some(Self::zero());
+        }
+
+        let g = self.gcd(other);
+        return (self.v / g.v).checked_mul(other.v).map(|v| Self { v });
+    }
+
+    pub fn is_coprime(&self, other: &Self) -> bool {
+        return self.gcd(other).is_one();
+    }
+
+    /// Classic extended Euclidean algorithm. Returns (g, x, y) with
+    /// a*x + b*y = g = gcd(a, b).
+    ///
+    /// The coefficients follow s(i+1) = s(i-1) - q(i) * s(i), and their signs
+    /// alternate from step to step (s is negative on odd steps, t on even
+    /// ones), so only the magnitudes are tracked:
+    ///
+    /// |s(i+1)| = |s(i-1)| + q(i) * |s(i)|
+    pub fn extended_gcd(&self, b: &Self) -> (Self, BezoutCoefficient, BezoutCoefficient) {
+        let (mut old_r, mut r) = (self.v, b.v);
+        let (mut old_s, mut s) = (PU256::one(), PU256::zero());
+        let (mut old_t, mut t) = (PU256::zero(), PU256::one());
+
+        let mut step = 0;
+        while !r.is_zero() {
+            let (q, rem) = old_r.div_mod(r);
+            (old_r, r) = (r, rem);
+            (old_s, s) = (s, old_s + q * s);
+            (old_t, t) = (t, old_t + q * t);
+            step += 1;
+        }
+
+        let x = BezoutCoefficient {
+            negative: step % 2 == 1 && !old_s.is_zero(),
+            magnitude: Self { v: old_s },
+        };
+        let y = BezoutCoefficient {
+            negative: step % 2 == 0 && !old_t.is_zero(),
+            magnitude: Self { v: old_t },
+        };
+
+        return (Self { v: old_r }, x, y);
+    }
+
+    /// a^-1 (mod m) from `extended_gcd`: a*x + m*y = 1 means a*x = 1 (mod m).
+    /// Works for any modulus, prime or not. None if gcd(a, m) != 1, or if
+    /// m is zero.
+    pub fn inv_mod(&self, m: &Self) -> Option<Self> {
+        let a = Self {
+            v: self.v.checked_rem(m.v)?,
+        };
+        let (g, x, _) = a.extended_gcd(m);
+        if !g.is_one() {
+            return None;
+        }
+
+        let x_mod = Self {
+            v: x.magnitude.v % m.v,
+        };
+        if x.negative {
+            return Some(x_mod.neg_mod(m));
+        }
+        return Some(x_mod);
+    }
+
+    /// Legendre symbol (a/p) for an odd prime p, by Euler's criterion
+    /// a^((p - 1) / 2) (mod p): 1 if a is a nonzero square, -1 if it is not,
+    /// 0 if p divides a.
+    pub fn legendre(&self, p: &Self) -> i8 {
+        let e = Self { v: (p.v - 1) >> 1 };
+        let r = self.exp_mod(&e, p);
+        if r.is_zero() {
+            return 0;
+        }
+        if r.is_one() {
+            return 1;
+        }
+        return -1;
+    }
+
+    /// True if a has a square root mod p, which includes 0.
+    pub fn is_quadratic_residue(&self, p: &Self) -> bool {
+        return self.legendre(p) >= 0;
+    }
+
+    /// Jacobi symbol (a/n) for odd n, equal to the Legendre symbol when n is
+    /// prime. Unlike `legendre`, 1 does not mean a is a square mod composite
+    /// n.
+    ///
+    /// Reduces by quadratic reciprocity using only shifts and subtraction:
+    /// - (2/n) = -1 iff n = 3, 5 (mod 8)
+    /// - (a/n) = -(n/a) iff a = n = 3 (mod 4), otherwise (a/n) = (n/a)
+    pub fn jacobi(&self, n: &Self) -> Result<i8, U256Error> {
+        if n.is_even() {
+            return Err(U256Error::EvenModulus);
+        }
+
+        let mut a = self.v % n.v;
+        let mut n = n.v;
+        let mut t = 1;
+        while !a.is_zero() {
+            let s = a.trailing_zeros();
+            a >>= s;
+            let n_mod_8 = n.low_u32() & 7;
+            if s & 1 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+                t = -t;
+            }
+
+            // Both odd now: swap so a < n, then a - n is even
+            if a < n {
+                std::mem::swap(&mut a, &mut n);
+                if a.low_u32() & 3 == 3 && n.low_u32() & 3 == 3 {
+                    t = -t;
+                }
+            }
+            a -= n;
+        }
+
+        if n == PU256::one() {
+            return Ok(t);
+        }
+        return Ok(0);
+    }
+
+    /// The smaller of the two square roots of a (mod p), r <= p - r, or None
+    /// if a is not a quadratic residue. Meant for prime p, see `ModCtx::sqrt`
+    /// for composites. Uses a^((p + 1) / 4) when p = 3 (mod 4), as for the
+    /// secp256k1 field, and Tonelli-Shanks otherwise.
+    ///
+    /// Panics on moduli that are zero, one, or even other than 2.
+    pub fn sqrt_mod(&self, p: &Self) -> Option<Self> {
+        if p.v == PU256::from(2) {
+            return Some(Self {
+                v: self.v & PU256::one(),
+            });
+        }
+
+        let ctx = ModCtx::new(*p).expect("odd-prime-modulus");
+        return ctx.sqrt(self);
+    }
+}
+
+impl PartialEq for U256 {
+    fn eq(&self, other: &Self) -> bool {
+        return self.v == other.v;
+    }
+}
+
+impl Eq for U256 {}
+
+/// Hashes the big-endian bytes, so equal values hash equally whatever the
+/// internal representation.
+impl Hash for U256 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_be_bytes().hash(state);
+    }
+}
+
+/// "0x" and 64 hex digits for human-readable formats like JSON, the 32
+/// big-endian bytes for binary ones.
+#[cfg(feature = "serde")]
+impl serde::Serialize for U256 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&format!("0x{}", self));
+        }
+        return serializer.serialize_bytes(&self.to_be_bytes());
+    }
+}
+
+/// Takes a hex string as `FromStr` does, or up to 32 big-endian bytes.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for U256 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return deserializer.deserialize_str(U256Visitor);
+        }
+        return deserializer.deserialize_bytes(U256Visitor);
+    }
+}
+
+#[cfg(feature = "serde")]
+struct U256Visitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for U256Visitor {
+    type Value = U256;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str("a hex string of at most 64 digits or at most 32 bytes");
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<U256, E> {
+        return U256::from_str(s).map_err(E::custom);
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, bs: &[u8]) -> Result<U256, E> {
+        return U256::try_from(bs).map_err(E::custom);
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+/// Numeric order, so `min`/`max`/`clamp` and sorting follow the value.
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        return self.v.cmp(&other.v);
+    }
+}
+
+/// Shifting by 256 or more gives zero rather than panicking.
+impl Shl<u32> for U256 {
+    type Output = Self;
+    fn shl(self, shift: u32) -> Self {
+        if shift >= 256 {
+            return Self::zero();
+        }
+        return Self {
+            v: self.v << shift as usize,
+        };
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = Self;
+    fn shr(self, shift: u32) -> Self {
+        if shift >= 256 {
+            return Self::zero();
+        }
+        return Self {
+            v: self.v >> shift as usize,
+        };
+    }
+}
+
+impl ShlAssign<u32> for U256 {
+    fn shl_assign(&mut self, shift: u32) {
+        *self = *self << shift;
+    }
+}
+
+impl ShrAssign<u32> for U256 {
+    fn shr_assign(&mut self, shift: u32) {
+        *self = *self >> shift;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modctx::ModCtx;
+    use crate::rng::TestRng;
+    use crate::u256::{
+        self, ArithError, BezoutCoefficient, LengthError, RlpIntError, U256Error, U256ParseError,
+        SMALL_PRIMES, U256,
+    };
+    use primitive_types::U256 as PU256;
+    use rand::RngCore;
+    use std::collections::{HashMap, HashSet};
+    use std::str::FromStr;
+
+    #[test]
+    fn addition_case_1() {
+        let a = U256::from_str("0xBD").unwrap();
+        let b = U256::from_str("0x2B").unwrap();
+        let p = U256::from_str("0xB").unwrap();
+
+        let r = a.add_mod(&b, &p);
+
+        assert_eq!(
+            r.to_string(),
+            "0000000000000000000000000000000000000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn addition_case_2() {
+        let a = U256::from_str("0xa167f055ff75c").unwrap();
+        let b = U256::from_str("0xacc457752e4ed").unwrap();
+        let p = U256::from_str("0xf9cd").unwrap();
+
+        let r = a.add_mod(&b, &p);
+
+        assert_eq!(
+            r.to_string(),
+            "0000000000000000000000000000000000000000000000000000000000006bb0"
+        );
+    }
+
+    #[test]
+    fn addition_case_3() {
         let a = U256::from_str("0xa167f055ff75c7f055ff7").unwrap();
         let b = U256::from_str("0x7752acc45e4acc45ed57752e").unwrap();
         let p = U256::from_str("0xf9caf05f05cc45d").unwrap();
@@ -378,4 +1290,1445 @@ mod tests {
             "0000000000000000000000000000000000000000000000000000000124207cf3"
         );
     }
+
+    #[test]
+    fn inv_mod() {
+        let p =
+            U256::from_str("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+                .unwrap();
+        let mut rng = TestRng::new(253);
+        for _ in 0..20 {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            let a = U256::from_be_bytes(&bytes);
+
+            let inv = a.inv_mod(&p).unwrap();
+            assert!(inv < p);
+            assert_eq!(a.mul_mod(&inv, &p), U256::one());
+            assert_eq!(inv, U256::one().div_mod(&a, &p));
+        }
+
+        // Composite modulus: 7 * 13 = 91
+        let m = U256::from_str("0x5b").unwrap();
+        let inv = |a: u8| U256::from_be_bytes(&[a]).inv_mod(&m);
+        assert_eq!(inv(2), Some(U256::new(46)));
+        assert_eq!(inv(90), Some(U256::new(90)));
+        assert_eq!(inv(93), inv(2));
+        assert_eq!(inv(7), None);
+        assert_eq!(inv(26), None);
+        assert_eq!(inv(0), None);
+        assert_eq!(inv(91), None);
+
+        // Even modulus, only odd values are invertible
+        let m = U256::new(0x80);
+        assert_eq!(U256::new(3).inv_mod(&m), Some(U256::new(43)));
+        assert_eq!(U256::new(4).inv_mod(&m), None);
+
+        assert_eq!(U256::new(5).inv_mod(&U256::one()), Some(U256::zero()));
+    }
+
+    #[test]
+    fn legendre_small_primes() {
+        for p in [3u64, 5, 7, 11, 13, 17, 101] {
+            let pu = U256::from_be_bytes(&p.to_be_bytes());
+            let squares: Vec<u64> = (1..p).map(|x| x * x % p).collect();
+
+            assert_eq!(U256::zero().legendre(&pu), 0);
+            assert_eq!(pu.legendre(&pu), 0);
+            for a in 1..p {
+                let expected = if squares.contains(&a) { 1 } else { -1 };
+                let au = U256::from_be_bytes(&a.to_be_bytes());
+                assert_eq!(au.legendre(&pu), expected, "({}/{})", a, p);
+                assert_eq!(au.is_quadratic_residue(&pu), expected == 1);
+            }
+        }
+        assert!(U256::zero().is_quadratic_residue(&U256::new(7)));
+    }
+
+    #[test]
+    fn legendre_secp256k1() {
+        let p =
+            U256::from_str("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+                .unwrap();
+        let gx =
+            U256::from_str("0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798")
+                .unwrap();
+
+        // Gx is on the curve, so Gx^3 + 7 is a square
+        let y2 = gx
+            .mul_mod(&gx, &p)
+            .mul_mod(&gx, &p)
+            .add_mod(&U256::new(7), &p);
+        assert_eq!(y2.legendre(&p), 1);
+        assert_eq!(U256::one().neg_mod(&p).legendre(&p), -1);
+    }
+
+    #[test]
+    fn jacobi_matches_legendre() {
+        let mut primes: Vec<U256> = SMALL_PRIMES[1..]
+            .iter()
+            .map(|p| U256::from_be_bytes(&p.to_be_bytes()))
+            .collect();
+        primes.push(
+            U256::from_str("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+                .unwrap(),
+        );
+        primes.push(
+            U256::from_str("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141")
+                .unwrap(),
+        );
+
+        let mut rng = TestRng::new(256);
+        for p in &primes {
+            let mut values: Vec<U256> = (0..40u8).map(|a| U256::from_be_bytes(&[a])).collect();
+            for _ in 0..10 {
+                let mut bytes = [0; 32];
+                rng.fill_bytes(&mut bytes);
+                values.push(U256::from_be_bytes(&bytes));
+            }
+            values.push(*p);
+            values.push(U256::one().neg_mod(p));
+
+            for a in values {
+                assert_eq!(a.jacobi(p), Ok(a.legendre(p)), "({}/{})", a, p);
+            }
+        }
+    }
+
+    #[test]
+    fn jacobi_composite() {
+        let jacobi = |a: u64, n: u64| {
+            return U256::from_be_bytes(&a.to_be_bytes())
+                .jacobi(&U256::from_be_bytes(&n.to_be_bytes()));
+        };
+
+        // (a/15) = (a/3)(a/5)
+        assert_eq!(jacobi(2, 15), Ok(1));
+        assert_eq!(jacobi(7, 15), Ok(-1));
+        assert_eq!(jacobi(5, 15), Ok(0));
+        assert_eq!(jacobi(1001, 9907), Ok(-1));
+        assert_eq!(jacobi(19, 45), Ok(1));
+        assert_eq!(jacobi(8, 21), Ok(-1));
+        assert_eq!(jacobi(5, 21), Ok(1));
+        assert_eq!(jacobi(0, 1), Ok(1));
+        assert_eq!(jacobi(30, 1), Ok(1));
+
+        // (2/15) = 1, but 2 is not a square mod 15
+        assert!((0..15u64).all(|x| x * x % 15 != 2));
+
+        assert_eq!(jacobi(3, 16), Err(U256Error::EvenModulus));
+        assert_eq!(jacobi(3, 0), Err(U256Error::EvenModulus));
+    }
+
+    #[test]
+    fn sqrt_mod_small_primes() {
+        // 3 mod 4, 1 mod 4 with s = 2, and s = 4
+        for p in [2u64, 3, 7, 13, 17, 41, 97, 257] {
+            let pu = U256::from_be_bytes(&p.to_be_bytes());
+            let squares: Vec<u64> = (0..p).map(|x| x * x % p).collect();
+
+            for a in 0..p {
+                let au = U256::from_be_bytes(&a.to_be_bytes());
+                match au.sqrt_mod(&pu) {
+                    Some(r) => {
+                        assert_eq!(r.mul_mod(&r, &pu), au, "{} mod {}", a, p);
+                        assert!(r <= r.neg_mod(&pu));
+                    }
+                    None => assert!(!squares.contains(&a), "{} mod {}", a, p),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_mod_secp256k1() {
+        let p =
+            U256::from_str("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+                .unwrap();
+        let gx =
+            U256::from_str("0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798")
+                .unwrap();
+        let gy =
+            U256::from_str("0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8")
+                .unwrap();
+
+        // Decompress G, y^2 = x^3 + 7. Gy is the smaller root
+        let y2 = gx
+            .mul_mod(&gx, &p)
+            .mul_mod(&gx, &p)
+            .add_mod(&U256::new(7), &p);
+        assert_eq!(y2.sqrt_mod(&p), Some(gy));
+
+        assert_eq!(U256::zero().sqrt_mod(&p), Some(U256::zero()));
+        assert_eq!(p.sqrt_mod(&p), Some(U256::zero()));
+        // -1 is a non-residue when p = 3 (mod 4)
+        assert_eq!(U256::one().neg_mod(&p).sqrt_mod(&p), None);
+    }
+
+    #[test]
+    fn neg_mod() {
+        let p = U256::from_str("0xf9cd").unwrap();
+        let neg = |x: &str| U256::from_str(x).unwrap().neg_mod(&p);
+
+        assert_eq!(neg("0x0"), U256::zero());
+        assert_eq!(neg("0xf9cd"), U256::zero());
+        assert_eq!(neg("0x1"), U256::from_str("0xf9cc").unwrap());
+        assert_eq!(neg("0xf9cc"), U256::one());
+        // p + 5 and 3p + 5 are both 5
+        assert_eq!(neg("0xf9d2"), U256::from_str("0xf9c8").unwrap());
+        assert_eq!(neg("0x2ed6c"), U256::from_str("0xf9c8").unwrap());
+
+        let x = U256::from_be_bytes(&[0xab; 32]);
+        let big_p =
+            U256::from_str("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+                .unwrap();
+        assert_eq!(x.add_mod(&x.neg_mod(&big_p), &big_p), U256::zero());
+        assert_eq!(x.neg_mod(&big_p).neg_mod(&big_p), U256 { v: x.v % big_p.v });
+    }
+
+    #[test]
+    fn ordering() {
+        let half =
+            U256::from_str("0x8000000000000000000000000000000000000000000000000000000000000000")
+                .unwrap();
+        let below =
+            U256::from_str("0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
+                .unwrap();
+        let max = U256::from_be_bytes(&[0xff; 32]);
+
+        assert!(below < half);
+        assert!(half < max);
+        assert!(U256::zero() < U256::one());
+        assert_eq!(half.cmp(&half), std::cmp::Ordering::Equal);
+
+        // Leading zeros and different lengths compare by value
+        assert_eq!(
+            U256::from_str("0x00000000ff").unwrap(),
+            U256::from_str("ff").unwrap()
+        );
+        assert!(U256::from_str("0x100").unwrap() > U256::from_str("0xff").unwrap());
+        assert!(
+            U256::from_str("0x1000000000000000000").unwrap()
+                > U256::from_str("0xffffffffffffffff").unwrap()
+        );
+
+        assert_eq!(half.min(below), below);
+        assert_eq!(half.max(below), half);
+
+        let mut values = vec![max, U256::zero(), half, below];
+        values.sort();
+        assert_eq!(values, vec![U256::zero(), below, half, max]);
+    }
+
+    #[test]
+    fn write_hex_fixed() {
+        let values = [
+            "0x0",
+            "0x1",
+            "0xBD",
+            "0xa167f055ff75c7f055ff7",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+        ];
+
+        for v in values {
+            let a = U256::from_str(v).unwrap();
+            let mut out = [0; 64];
+            a.write_hex_fixed(&mut out);
+            assert_eq!(std::str::from_utf8(&out).unwrap(), a.to_string());
+        }
+    }
+
+    #[test]
+    fn from_hex_str_matches_from_str() {
+        let mut rng = TestRng::new(235);
+        let alphabet = b"0123456789abcdefABCDEF";
+        for len in 1..=64 {
+            for _ in 0..20 {
+                let s: String = (0..len)
+                    .map(|_| alphabet[(rng.next_u32() % 22) as usize] as char)
+                    .collect();
+                let prefixed = format!("0x{}", s);
+
+                let expected = U256::from_str(&s).unwrap();
+                assert_eq!(U256::from_hex_str(&s), Ok(expected), "{}", s);
+                assert_eq!(U256::from_hex_str(&prefixed), Ok(expected));
+            }
+        }
+
+        for s in ["", "0x", "0xg", "12 3", "0x0x1", "é", &"f".repeat(65)] {
+            assert_eq!(U256::from_hex_str(s), U256::from_str(s), "{}", s);
+        }
+    }
+
+    #[test]
+    fn try_mod_zero_and_one() {
+        let a = U256::from_be_bytes(&[0x12, 0x34]);
+        let b = U256::new(0x56);
+        let zero = U256::zero();
+        let one = U256::one();
+
+        assert_eq!(a.try_add_mod(&b, &zero), Err(ArithError::ZeroModulus));
+        assert_eq!(a.try_sub_mod(&b, &zero), Err(ArithError::ZeroModulus));
+        assert_eq!(a.try_mul_mod(&b, &zero), Err(ArithError::ZeroModulus));
+        assert_eq!(a.try_exp_mod(&b, &zero), Err(ArithError::ZeroModulus));
+        assert_eq!(a.try_div_mod(&b, &zero), Err(ArithError::ZeroModulus));
+        assert_eq!(a.try_neg_mod(&zero), Err(ArithError::ZeroModulus));
+        assert_eq!(a.inv_mod(&zero), None);
+
+        // Every residue mod 1 is 0, including x^0
+        assert_eq!(a.try_add_mod(&b, &one), Ok(zero));
+        assert_eq!(a.try_sub_mod(&b, &one), Ok(zero));
+        assert_eq!(a.try_mul_mod(&b, &one), Ok(zero));
+        assert_eq!(a.try_exp_mod(&b, &one), Ok(zero));
+        assert_eq!(a.try_exp_mod(&zero, &one), Ok(zero));
+        assert_eq!(a.try_div_mod(&b, &one), Ok(zero));
+        assert_eq!(a.try_div_mod(&zero, &one), Ok(zero));
+    }
+
+    #[test]
+    fn try_div_mod() {
+        let p = U256::new(101);
+        let a = U256::new(7);
+        let b = U256::new(33);
+        assert_eq!(a.try_div_mod(&b, &p), Ok(a.div_mod(&b, &p)));
+        assert_eq!(
+            a.try_div_mod(&U256::zero(), &p),
+            Err(ArithError::NonInvertible)
+        );
+        assert_eq!(a.try_div_mod(&p, &p), Err(ArithError::NonInvertible));
+
+        // Composite modulus: 7 / 2 = 7 * 46 = 49 (mod 91), 7 has no inverse
+        let m = U256::new(91);
+        assert_eq!(a.try_div_mod(&U256::new(2), &m), Ok(U256::new(49)));
+        assert_eq!(b.try_div_mod(&a, &m), Err(ArithError::NonInvertible));
+    }
+
+    #[test]
+    fn formatting() {
+        let zero = U256::zero();
+        assert_eq!(format!("{}", zero), "0".repeat(64));
+        assert_eq!(format!("{:x}", zero), "0");
+        assert_eq!(format!("{:#x}", zero), "0x0");
+        assert_eq!(format!("{:X}", zero), "0");
+        assert_eq!(format!("{:#X}", zero), "0x0");
+
+        let a = U256::new(0xa);
+        assert_eq!(format!("{}", a), format!("{}a", "0".repeat(63)));
+        assert_eq!(format!("{:x}", a), "a");
+        assert_eq!(format!("{:#x}", a), "0xa");
+        assert_eq!(format!("{:X}", a), "A");
+        assert_eq!(format!("{:#X}", a), "0xA");
+        assert_eq!(format!("{:#06x}", a), "0x000a");
+
+        let n = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+        let n = U256::from_str(n).unwrap();
+        assert_eq!(format!("{}", n), n.to_string());
+        assert_eq!(
+            format!("{}", n),
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+        );
+        assert_eq!(
+            format!("{:x}", n),
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+        );
+        assert_eq!(
+            format!("{:#X}", n),
+            "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141"
+        );
+    }
+
+    #[test]
+    fn to_be_bytes() {
+        let a = U256::from_str("0102030405060708111213141516171821222324252627283132333435363738")
+            .unwrap();
+        let bytes = a.to_be_bytes();
+        assert_eq!(bytes[0], 0x01);
+        assert_eq!(bytes[31], 0x38);
+        assert_eq!(U256::from_be_bytes(&bytes), a);
+        assert_eq!(U256::zero().to_be_bytes(), [0; 32]);
+
+        let mut out = [0; 32];
+        assert_eq!(a.to_bytes_into(&mut out), Ok(()));
+        assert_eq!(out, bytes);
+
+        for len in [0, 1, 31, 33, 64] {
+            let mut out = vec![0xaa; len];
+            assert_eq!(
+                a.to_bytes_into(&mut out),
+                Err(LengthError {
+                    expected: 32,
+                    got: len
+                })
+            );
+            assert!(out.iter().all(|b| *b == 0xaa));
+        }
+    }
+
+    #[test]
+    fn bit_accessors() {
+        for i in 0..256 {
+            let pow2 = U256 {
+                v: PU256::one() << i,
+            };
+            assert_eq!(pow2.bit_len(), i + 1);
+            assert_eq!(pow2.leading_zeros(), 255 - i as u32);
+            for j in 0..256 {
+                assert_eq!(pow2.bit(j), i == j);
+            }
+        }
+
+        let p = U256::from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+            .unwrap();
+        assert_eq!(p.bit_len(), 256);
+        assert_eq!(p.leading_zeros(), 0);
+        // ...fffffc2f = ...1111 1100 0010 1111
+        let low: Vec<bool> = (0..12).map(|i| p.bit(i)).collect();
+        let expected = [1, 1, 1, 1, 0, 1, 0, 0, 0, 0, 1, 1];
+        assert_eq!(low, expected.map(|b| b == 1));
+        assert!(!p.bit(32));
+        assert!(p.bit(33));
+        assert!(!p.bit(256));
+
+        let zero = U256::zero();
+        assert_eq!(zero.bit_len(), 0);
+        assert_eq!(zero.leading_zeros(), 256);
+        assert!((0..300).all(|i| !zero.bit(i)));
+
+        let mut rng = TestRng::new(266);
+        for _ in 0..50 {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            let a = U256::from_be_bytes(&bytes[..(rng.next_u32() % 33) as usize]);
+            assert_eq!(a.bit_len() + a.leading_zeros() as usize, 256);
+            if a.bit_len() > 0 {
+                assert!(a.bit(a.bit_len() - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn is_zero_is_one() {
+        let p = U256::from_str("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+            .unwrap();
+        let zeros = [
+            U256::zero(),
+            U256::from_str("0x0").unwrap(),
+            U256::from_hex("0000").unwrap(),
+            U256::from_be_bytes(&[]),
+            U256::from_be_bytes(&[0; 32]),
+            U256::from(0u64),
+            p.sub_mod(&p, &p),
+            p.mul_mod(&U256::from(5u8), &p),
+            U256::one() << 256,
+        ];
+        for z in &zeros {
+            assert!(z.is_zero(), "{}", z);
+            assert!(!z.is_one(), "{}", z);
+        }
+
+        let ones = [
+            U256::one(),
+            U256::from_str("0x1").unwrap(),
+            U256::from_be_bytes(&[0, 0, 1]),
+            U256::from(1u128),
+            U256::from_dec_str("0001").unwrap(),
+            U256::from(2u8).div_mod(&U256::from(2u8), &p),
+            U256::from(3u8).exp_mod(&U256::zero(), &p),
+        ];
+        for o in &ones {
+            assert!(o.is_one(), "{}", o);
+            assert!(!o.is_zero(), "{}", o);
+        }
+
+        for n in [U256::from(2u8), p, U256::one() << 64, U256::one() << 255] {
+            assert!(!n.is_zero() && !n.is_one(), "{}", n);
+        }
+    }
+
+    #[test]
+    fn parity() {
+        let p = U256::from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+            .unwrap();
+        let p_minus_1 = p.sub_mod(&U256::one(), &U256 { v: PU256::MAX });
+        let cases = [
+            (U256::zero(), 0),
+            (U256::one(), 1),
+            (p, 1),
+            (p_minus_1, 0),
+            (U256 { v: PU256::MAX }, 1),
+            (U256::one() << 255, 0),
+        ];
+        for (a, parity) in cases {
+            assert_eq!(a.parity(), parity, "{}", a);
+            assert_eq!(a.is_even(), parity == 0);
+            assert_eq!(a.is_odd(), parity == 1);
+        }
+    }
+
+    #[test]
+    fn shifts() {
+        let x = U256::from_hex("8000000000000000000000000000000000000000000000000000000000000001")
+            .unwrap();
+        let one = U256::one();
+
+        assert_eq!(x << 0, x);
+        assert_eq!(x >> 0, x);
+        assert_eq!(one << 1, U256::from(2u8));
+        assert_eq!(x << 1, U256::from(2u8));
+        assert_eq!(
+            x >> 1,
+            U256::from_hex(&format!("4{}", "0".repeat(63))).unwrap()
+        );
+        assert_eq!(
+            one << 255,
+            U256::from_hex(&format!("8{}", "0".repeat(63))).unwrap()
+        );
+        assert_eq!(x >> 255, one);
+        for shift in [256, 300, u32::MAX] {
+            assert_eq!(x << shift, U256::zero());
+            assert_eq!(x >> shift, U256::zero());
+        }
+
+        assert_eq!(one.wrapping_shl(256), one);
+        assert_eq!(one.wrapping_shl(257), U256::from(2u8));
+        assert_eq!(x.wrapping_shr(300), x >> 44);
+
+        let mut y = x;
+        y <<= 4;
+        y >>= 4;
+        assert_eq!(y, U256::one());
+
+        // x << k >> k keeps the low 256 - k bits
+        let mut rng = TestRng::new(267);
+        for _ in 0..50 {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            let a = U256::from_be_bytes(&bytes);
+            let k = rng.next_u32() % 257;
+            let low = (0..256).all(|i| (a << k >> k).bit(i) == (a.bit(i) && i < 256 - k as usize));
+            assert!(low, "{} << {}", a, k);
+        }
+    }
+
+    #[test]
+    fn from_integers() {
+        assert_eq!(U256::from(0u8), U256::zero());
+        assert_eq!(U256::from(7u8), U256::from_be_bytes(&[7]));
+        assert_eq!(U256::from(u32::MAX), U256::from_be_bytes(&[0xff; 4]));
+        assert_eq!(U256::from(u64::MAX), U256::from_be_bytes(&[0xff; 8]));
+        assert_eq!(U256::from(u128::MAX), U256::from_be_bytes(&[0xff; 16]));
+
+        assert_eq!(u64::try_from(U256::from(u64::MAX)), Ok(u64::MAX));
+        assert_eq!(u64::try_from(U256::from(0u8)), Ok(0));
+        assert_eq!(u128::try_from(U256::from(u128::MAX)), Ok(u128::MAX));
+        assert_eq!(u128::try_from(U256::from(u64::MAX)), Ok(u64::MAX as u128));
+
+        let just_over_u64 = U256::from(u64::MAX as u128 + 1);
+        assert_eq!(u64::try_from(just_over_u64), Err(U256Error::Overflow));
+        assert_eq!(u128::try_from(just_over_u64), Ok(1 << 64));
+        let just_over_u128 = U256::from_be_bytes(&[[1].as_slice(), &[0; 16]].concat());
+        assert_eq!(u128::try_from(just_over_u128), Err(U256Error::Overflow));
+        assert_eq!(
+            u64::try_from(U256 { v: PU256::MAX }),
+            Err(U256Error::Overflow)
+        );
+    }
+
+    #[test]
+    fn try_from_slice() {
+        let bytes: Vec<u8> = (1..=33).collect();
+
+        assert_eq!(U256::try_from(&bytes[..0]), Ok(U256::zero()));
+        assert_eq!(
+            U256::try_from(&bytes[..31]),
+            Ok(U256::from_be_bytes(&bytes[..31]))
+        );
+        let a = U256::try_from(&bytes[..32]).unwrap();
+        assert_eq!(a.to_be_bytes()[..], bytes[..32]);
+        assert_eq!(U256::try_from(&bytes[1..32]).unwrap().to_be_bytes()[0], 0);
+
+        let err = U256::try_from(&bytes[..]).unwrap_err();
+        assert_eq!(
+            err,
+            LengthError {
+                expected: 32,
+                got: 33
+            }
+        );
+        assert_eq!(err.to_string(), "expected 32 bytes, got 33");
+
+        let array: [u8; 32] = bytes[..32].try_into().unwrap();
+        assert_eq!(U256::from(array), a);
+    }
+
+    #[test]
+    fn le_bytes() {
+        let a = U256::from_str("0102030405060708111213141516171821222324252627283132333435363738")
+            .unwrap();
+        let le = a.to_le_bytes();
+        assert_eq!(le[0], 0x38);
+        assert_eq!(le[31], 0x01);
+        let mut be = a.to_be_bytes();
+        be.reverse();
+        assert_eq!(le, be);
+
+        assert_eq!(U256::from_le_bytes(&le), Ok(a));
+        assert_eq!(
+            U256::from_le_bytes(&[0x01, 0x02]),
+            Ok(U256::from_be_bytes(&[0x02, 0x01]))
+        );
+        assert_eq!(U256::from_le_bytes(&[]), Ok(U256::zero()));
+        assert_eq!(
+            U256::from_le_bytes(&[0; 33]),
+            Err(LengthError {
+                expected: 32,
+                got: 33
+            })
+        );
+
+        let mut rng = TestRng::new(263);
+        for _ in 0..100 {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            let x = U256::from_be_bytes(&bytes);
+            assert_eq!(U256::from_le_bytes(&x.to_le_bytes()), Ok(x));
+            assert_eq!(U256::from_be_bytes(&x.to_be_bytes()), x);
+        }
+    }
+
+    #[test]
+    fn hash_map_keys() {
+        let p = U256::from_str("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+            .unwrap();
+        let mut map = HashMap::new();
+        map.insert(U256::from_str("0x2a").unwrap(), "hex");
+        map.insert(U256::from(42u8), "u8");
+        map.insert(U256::from_dec_str("42").unwrap(), "dec");
+        map.insert(U256::from(6u8).mul_mod(&U256::from(7u8), &p), "mul");
+        map.insert(U256::from_be_bytes(&[0, 0, 42]), "bytes");
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[&(U256::from(21u8) << 1)], "bytes");
+
+        map.insert(U256::zero(), "zero");
+        map.insert(p, "p");
+        map.insert(
+            p.sub_mod(&U256::one(), &U256::from_limbs([u64::MAX; 4])),
+            "p-1",
+        );
+        assert_eq!(map.len(), 4);
+        assert_eq!(map[&U256::from_str("0x0").unwrap()], "zero");
+
+        let set: HashSet<U256> = [
+            U256::one(),
+            U256::from_str("1").unwrap(),
+            U256::from(3u8).exp_mod(&U256::zero(), &p),
+            U256::from(2u8),
+            U256::one() << 1,
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn copy_semantics() {
+        let p = U256::from(101u8);
+        let ctx = ModCtx::new(p).unwrap();
+        assert_eq!(ctx.modulus(), p);
+
+        let a = U256::from(7u8);
+        let shifted = a << 1;
+        assert_eq!(shifted, U256::from(14u8));
+        assert_eq!(a, U256::from(7u8));
+        assert_eq!(u64::try_from(a), Ok(7));
+        assert_eq!(a.add_mod(&a, &p), shifted);
+    }
+
+    #[test]
+    fn default_and_new() {
+        #[derive(Default)]
+        struct Entry {
+            k: U256,
+            hits: u32,
+        }
+
+        assert_eq!(U256::default(), U256::zero());
+        let e = Entry::default();
+        assert!(e.k.is_zero());
+        assert_eq!(e.hits, 0);
+
+        const SIXTEEN: U256 = U256::new(0x10);
+        assert_eq!(SIXTEEN, U256::from_str("0x10").unwrap());
+        assert_eq!(U256::new(u64::MAX), U256::from(u64::MAX));
+    }
+
+    #[test]
+    fn const_zero_one() {
+        const TWO: U256 = U256::from_limbs([2, 0, 0, 0]);
+        assert_eq!(U256::ZERO, U256::from_str("0x0").unwrap());
+        assert_eq!(U256::ONE, U256::from_str("0x1").unwrap());
+        assert_eq!(TWO, U256::from_str("0x2").unwrap());
+        assert_eq!(U256::zero(), U256::ZERO);
+        assert_eq!(U256::one(), U256::ONE);
+    }
+
+    #[test]
+    fn limbs() {
+        let a = U256::from_str("0102030405060708111213141516171821222324252627283132333435363738")
+            .unwrap();
+        let limbs = [
+            0x3132333435363738,
+            0x2122232425262728,
+            0x1112131415161718,
+            0x0102030405060708,
+        ];
+        assert_eq!(a.as_limbs(), &limbs);
+        assert_eq!(U256::from_limbs(limbs), a);
+        assert_eq!(U256::from_limbs([1, 0, 0, 0]), U256::one());
+    }
+
+    #[test]
+    fn integer_arithmetic() {
+        let (max, one, two) = (U256::MAX, U256::ONE, U256::new(2));
+        assert_eq!(max.overflowing_add(&one), (U256::ZERO, true));
+        assert_eq!(two.overflowing_add(&one), (U256::new(3), false));
+        assert_eq!(U256::ZERO.overflowing_sub(&one), (max, true));
+        assert_eq!(max.wrapping_mul(&two), max.overflowing_sub(&one).0);
+
+        assert_eq!(max.checked_add(&one), None);
+        assert_eq!(one.checked_sub(&two), None);
+        assert_eq!(max.checked_mul(&two), None);
+        assert_eq!(two.checked_mul(&two), Some(U256::new(4)));
+
+        assert_eq!(U256::new(100).div_rem(&U256::new(36)), (two, U256::new(28)));
+        assert_eq!(max.div_rem(&max), (one, U256::ZERO));
+
+        // (2^256 - 1)^2 = (2^256 - 2)·2^256 + 1
+        assert_eq!(max.full_mul(&max), (max.overflowing_sub(&one).0, one));
+        assert_eq!(two.full_mul(&two), (U256::ZERO, U256::new(4)));
+        // 2^256 mod (2^256 - 1) = 1
+        assert_eq!(U256::rem_wide(&one, &U256::ZERO, &max), one);
+        // 2^512 - 1 mod 7 = 3, as 2^3 = 1 (mod 7)
+        assert_eq!(U256::rem_wide(&max, &max, &U256::new(7)), U256::new(3));
+        assert_eq!(U256::new(12).trailing_zeros(), 2);
+        assert_eq!(U256::ZERO.trailing_zeros(), 256);
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_rem_by_zero() {
+        U256::ONE.div_rem(&U256::ZERO);
+    }
+
+    #[test]
+    fn zeroize() {
+        let mut k = U256::from_limbs([u64::MAX, 1, 2, 3]);
+        let copy = k;
+        k.zeroize();
+        assert_eq!(k.as_limbs(), &[0; 4]);
+        assert!(k.is_zero());
+        // Copies are separate values, untouched
+        assert_eq!(copy.as_limbs(), &[u64::MAX, 1, 2, 3]);
+    }
+
+    #[test]
+    fn dec_str() {
+        let n = U256::from_str("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141")
+            .unwrap();
+        let n_dec =
+            "115792089237316195423570985008687907852837564279074904382605163141518161494337";
+        assert_eq!(n.to_dec_string(), n_dec);
+        assert_eq!(U256::from_dec_str(n_dec), Ok(n));
+        assert_eq!(U256::from_dec_str(&format!("000{}", n_dec)), Ok(n));
+
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(U256::from_dec_str(max), Ok(U256 { v: PU256::MAX }));
+        assert_eq!(U256 { v: PU256::MAX }.to_dec_string(), max);
+
+        assert_eq!(U256::zero().to_dec_string(), "0");
+        assert_eq!(U256::from_dec_str("000"), Ok(U256::zero()));
+        assert_eq!(U256::from_be_bytes(&[0x01, 0x00]).to_dec_string(), "256");
+
+        let mut rng = TestRng::new(257);
+        for _ in 0..50 {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            let a = U256::from_be_bytes(&bytes[..(rng.next_u32() % 33) as usize]);
+            assert_eq!(U256::from_dec_str(&a.to_dec_string()), Ok(a));
+        }
+
+        // 2^256 overflows
+        let over = "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        let invalid = |position, character| {
+            return Err(U256ParseError::InvalidCharacter {
+                position,
+                character,
+            });
+        };
+        assert_eq!(U256::from_dec_str(""), Err(U256ParseError::Empty));
+        assert_eq!(U256::from_dec_str(" 1"), invalid(0, ' '));
+        assert_eq!(U256::from_dec_str("1 "), invalid(1, ' '));
+        assert_eq!(U256::from_dec_str("-1"), invalid(0, '-'));
+        assert_eq!(U256::from_dec_str("+1"), invalid(0, '+'));
+        assert_eq!(U256::from_dec_str("0x10"), invalid(1, 'x'));
+        assert_eq!(U256::from_dec_str("1_000"), invalid(1, '_'));
+        assert_eq!(U256::from_dec_str("١"), invalid(0, '١'));
+        assert_eq!(U256::from_dec_str(over), Err(U256ParseError::Overflow));
+    }
+
+    #[test]
+    fn from_str_accepted_forms() {
+        let expected = U256::from_be_bytes(&[0xab, 0xcd]);
+        for s in [
+            "abcd",
+            "ABCD",
+            "0xabcd",
+            "0XABCD",
+            "0xAbCd",
+            "  0xabcd\n",
+            "\tABCD ",
+            "00abcd",
+        ] {
+            assert_eq!(U256::from_str(s), Ok(expected), "{:?}", s);
+            assert_eq!(U256::from_hex(s), Ok(expected), "{:?}", s);
+        }
+
+        let max = format!("0x{}", "F".repeat(64));
+        assert_eq!(U256::from_str(&max), Ok(U256 { v: PU256::MAX }));
+        assert_eq!(
+            U256::from_str(&format!(" {}1 ", max)),
+            Err(U256ParseError::TooLong { len: 65 })
+        );
+        assert_eq!(U256::from_str("   "), Err(U256ParseError::Empty));
+        assert_eq!(U256::from_str(" 0X "), Err(U256ParseError::Empty));
+        assert_eq!(
+            U256::from_str("  0x1 2"),
+            Err(U256ParseError::InvalidCharacter {
+                position: 5,
+                character: ' '
+            })
+        );
+        assert_eq!(
+            U256::from_str("\u{3000}0x1g"),
+            Err(U256ParseError::InvalidCharacter {
+                position: 4,
+                character: 'g'
+            })
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let invalid = |position, character| {
+            return Err(U256ParseError::InvalidCharacter {
+                position,
+                character,
+            });
+        };
+        assert_eq!(U256::from_str(""), Err(U256ParseError::Empty));
+        assert_eq!(U256::from_str("0x"), Err(U256ParseError::Empty));
+        assert_eq!(U256::from_str("g"), invalid(0, 'g'));
+        assert_eq!(U256::from_str("0x12g4"), invalid(4, 'g'));
+        assert_eq!(U256::from_str("12 3"), invalid(2, ' '));
+        assert_eq!(U256::from_str("0x0x1"), invalid(3, 'x'));
+        assert_eq!(U256::from_str("abé"), invalid(2, 'é'));
+        assert_eq!(
+            U256::from_str(&"f".repeat(65)),
+            Err(U256ParseError::TooLong { len: 65 })
+        );
+        assert_eq!(
+            U256::from_str(&format!("0x{}", "0".repeat(65))),
+            Err(U256ParseError::TooLong { len: 65 })
+        );
+        // A bad character is reported even past the 64th digit
+        assert_eq!(
+            U256::from_str(&format!("{}g", "f".repeat(70))),
+            invalid(70, 'g')
+        );
+        assert_eq!(U256::from_str(&"f".repeat(64)), Ok(U256 { v: PU256::MAX }));
+
+        assert_eq!(
+            U256::from_str("0x12g4").unwrap_err().to_string(),
+            "invalid character 'g' at position 4"
+        );
+        assert_eq!(
+            U256::from_str(&"f".repeat(65)).unwrap_err().to_string(),
+            "65 hex digits, at most 64 fit"
+        );
+        let err: Box<dyn std::error::Error> = Box::new(U256ParseError::Empty);
+        assert_eq!(err.to_string(), "empty number");
+    }
+
+    /// a*x + b*y, wrapping at 2^256. Equal to g exactly when the Bezout
+    /// identity holds, since g < 2^256.
+    fn bezout_sum(a: &U256, x: &BezoutCoefficient, b: &U256, y: &BezoutCoefficient) -> U256 {
+        let term = |n: &U256, c: &BezoutCoefficient| {
+            let prod = n.v.overflowing_mul(c.magnitude.v).0;
+            if c.negative {
+                return prod.overflowing_neg().0;
+            }
+            return prod;
+        };
+        return U256 {
+            v: term(a, x).overflowing_add(term(b, y)).0,
+        };
+    }
+
+    #[test]
+    fn gcd_boundaries() {
+        let zero = U256::zero();
+        let max =
+            U256::from_str("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
+                .unwrap();
+        let x = U256::from_str("0x1234").unwrap();
+
+        assert_eq!(zero.gcd(&x), x);
+        assert_eq!(x.gcd(&zero), x);
+        assert_eq!(zero.gcd(&zero), zero);
+        assert_eq!(max.gcd(&max), max);
+        assert_eq!(max.gcd(&U256::one()), U256::one());
+        // 2^256 - 1 = 3 * 5 * 17 * 257 * ...
+        let ff = U256::from_str("0xff").unwrap();
+        assert_eq!(max.gcd(&ff), ff);
+
+        // even/even and even/odd
+        let a = U256::from_str("0x30").unwrap();
+        let b = U256::from_str("0x48").unwrap();
+        assert_eq!(a.gcd(&b), U256::from_str("0x18").unwrap());
+        let c = U256::from_str("0x2d").unwrap();
+        assert_eq!(a.gcd(&c), U256::from_str("0x3").unwrap());
+    }
+
+    #[test]
+    fn gcd_matches_euclid() {
+        let mut rng = TestRng::new(217);
+        for _ in 0..200 {
+            let mut bytes = [0; 64];
+            rng.fill_bytes(&mut bytes);
+            // Vary the sizes so both small and full-width values are covered
+            let la = (rng.next_u32() % 33) as usize;
+            let lb = (rng.next_u32() % 33) as usize;
+            let a = U256::from_be_bytes(&bytes[..la]);
+            let b = U256::from_be_bytes(&bytes[32..32 + lb]);
+
+            let (mut x, mut y) = (a.v, b.v);
+            while !y.is_zero() {
+                (x, y) = (y, x % y);
+            }
+
+            assert_eq!(a.gcd(&b).v, x);
+            assert_eq!(a.extended_gcd(&b).0.v, x);
+        }
+    }
+
+    #[test]
+    fn lcm_and_coprime() {
+        let a = U256::from_str("0x4").unwrap();
+        let b = U256::from_str("0x6").unwrap();
+        assert_eq!(a.lcm(&b), Some(U256::from_str("0xc").unwrap()));
+        assert_eq!(a.lcm(&U256::zero()), Some(U256::zero()));
+
+        let big =
+            U256::from_str("0x8000000000000000000000000000000000000000000000000000000000000000")
+                .unwrap();
+        assert_eq!(big.lcm(&U256::from_str("0x3").unwrap()), None);
+        assert_eq!(big.lcm(&a), Some(big));
+
+        assert!(!a.is_coprime(&b));
+        assert!(U256::from_str("0x9")
+            .unwrap()
+            .is_coprime(&U256::from_str("0x10").unwrap()));
+        assert!(!U256::zero().is_coprime(&a));
+    }
+
+    #[test]
+    fn extended_gcd_bezout_identity() {
+        let p = U256::from_str("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+            .unwrap();
+        let pairs = [
+            ("0xf0", "0x2e"),
+            ("0x2e", "0xf0"),
+            ("0x0", "0x5"),
+            ("0x5", "0x0"),
+            ("0x123456789abcdef", "0xfedcba9876543210"),
+            (
+                "0x1234",
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+            ),
+        ];
+
+        for (a, b) in pairs {
+            let a = U256::from_str(a).unwrap();
+            let b = U256::from_str(b).unwrap();
+            let (g, x, y) = a.extended_gcd(&b);
+            assert_eq!(g, a.gcd(&b));
+            assert_eq!(bezout_sum(&a, &x, &b, &y), g);
+        }
+
+        // 240 * -9 + 46 * 47 = 2
+        let (g, x, y) = U256::from_str("0xf0")
+            .unwrap()
+            .extended_gcd(&U256::from_str("0x2e").unwrap());
+        assert_eq!(g, U256::from_str("0x2").unwrap());
+        assert_eq!(
+            (x.negative, x.magnitude),
+            (true, U256::from_str("0x9").unwrap())
+        );
+        assert_eq!(
+            (y.negative, y.magnitude),
+            (false, U256::from_str("0x2f").unwrap())
+        );
+
+        // The coefficient of a is its inverse mod p
+        let a = U256::from_str("0x1234").unwrap();
+        let (_, x, _) = a.extended_gcd(&p);
+        let inv = if x.negative {
+            p.sub_mod(&x.magnitude, &p)
+        } else {
+            x.magnitude
+        };
+        assert_eq!(a.mul_mod(&inv, &p), U256::one());
+    }
+
+    #[test]
+    fn primality_curve_constants() {
+        let p = U256::from_str("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+            .unwrap();
+        let n = U256::from_str("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141")
+            .unwrap();
+
+        assert!(p.is_probable_prime(4));
+        assert!(n.is_probable_prime(4));
+        assert!(!U256 { v: p.v - 2 }.is_probable_prime(4));
+        assert_eq!(p.is_prime_deterministic(), None);
+    }
+
+    #[test]
+    fn primality_below_1000() {
+        let mut sieve = [true; 1000];
+        sieve[0] = false;
+        sieve[1] = false;
+        for i in 2..1000 {
+            if sieve[i] {
+                for j in (i * i..1000).step_by(i) {
+                    sieve[j] = false;
+                }
+            }
+        }
+
+        for (i, prime) in sieve.iter().enumerate() {
+            let n = U256::from_be_bytes(&(i as u64).to_be_bytes());
+            assert_eq!(n.is_probable_prime(4), *prime, "{}", i);
+            assert_eq!(n.is_prime_deterministic(), Some(*prime), "{}", i);
+        }
+    }
+
+    #[test]
+    fn primality_carmichael_numbers() {
+        let mut rng = TestRng::new(218);
+        let carmichael: [u64; 8] = [561, 1105, 1729, 2465, 2821, 6601, 8911, 9746347772161];
+        for c in carmichael {
+            let n = U256::from_be_bytes(&c.to_be_bytes());
+            assert!(!n.is_probable_prime_with_rng(8, &mut rng), "{}", c);
+            assert_eq!(n.is_prime_deterministic(), Some(false), "{}", c);
+        }
+
+        // Strong pseudoprime to every base up to 23, caught by 29..41
+        let spsp = U256::from_be_bytes(&3825123056546413051u64.to_be_bytes());
+        assert_eq!(spsp.is_prime_deterministic(), Some(false));
+        assert!(!spsp.is_probable_prime_with_rng(8, &mut rng));
+
+        let mersenne_61 = U256::from_be_bytes(&((1u64 << 61) - 1).to_be_bytes());
+        assert_eq!(mersenne_61.is_prime_deterministic(), Some(true));
+        assert!(mersenne_61.is_probable_prime_with_rng(8, &mut rng));
+        assert!(mersenne_61.is_probable_prime(8));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_deserialize() {
+        use serde::de::value::{BytesDeserializer, Error, StrDeserializer};
+        use serde::Deserialize;
+
+        fn from_str(s: &str) -> Result<U256, Error> {
+            return U256::deserialize(StrDeserializer::new(s));
+        }
+        fn from_bytes(bs: &[u8]) -> Result<U256, Error> {
+            return U256::deserialize(BytesDeserializer::new(bs));
+        }
+
+        // What Serialize emits, for each kind of format
+        let mut rng = TestRng::new(274);
+        for u in [U256::zero(), U256::one(), U256::from_limbs([u64::MAX; 4])]
+            .into_iter()
+            .chain((0..8).map(|_| U256::from_limbs([(); 4].map(|_| rng.next_u64()))))
+        {
+            assert_eq!(from_str(&format!("0x{}", u)).unwrap(), u);
+            assert_eq!(from_bytes(&u.to_be_bytes()).unwrap(), u);
+        }
+
+        assert_eq!(from_str("0x1f").unwrap(), U256::from(0x1fu8));
+        assert_eq!(from_bytes(&[1, 0]).unwrap(), U256::from(256u32));
+
+        let long = format!("0x1{}", "0".repeat(64));
+        assert_eq!(
+            from_str(&long).unwrap_err().to_string(),
+            U256ParseError::TooLong { len: 65 }.to_string()
+        );
+        assert!(from_str("0xg").is_err());
+        assert_eq!(
+            from_bytes(&[0; 33]).unwrap_err().to_string(),
+            "expected 32 bytes, got 33"
+        );
+    }
+
+    /// What a `Serializer` was handed, for the two forms `U256` uses.
+    #[cfg(feature = "serde")]
+    #[derive(Debug, PartialEq)]
+    enum Captured {
+        Str(String),
+        Bytes(Vec<u8>),
+    }
+
+    /// A serializer that takes only a string or bytes and records it, so
+    /// `Serialize` can be run without a format crate.
+    #[cfg(feature = "serde")]
+    struct Capture {
+        human_readable: bool,
+    }
+
+    #[cfg(feature = "serde")]
+    macro_rules! capture_unsupported {
+        ($($name:ident($($arg:ty),*)),* $(,)?) => {$(
+            fn $name(self, $(_: $arg),*) -> Result<Captured, serde::de::value::Error> {
+                return Err(serde::ser::Error::custom("unsupported"));
+            }
+        )*};
+    }
+
+    #[cfg(feature = "serde")]
+    impl serde::Serializer for Capture {
+        type Ok = Captured;
+        type Error = serde::de::value::Error;
+        type SerializeSeq = serde::ser::Impossible<Captured, Self::Error>;
+        type SerializeTuple = serde::ser::Impossible<Captured, Self::Error>;
+        type SerializeTupleStruct = serde::ser::Impossible<Captured, Self::Error>;
+        type SerializeTupleVariant = serde::ser::Impossible<Captured, Self::Error>;
+        type SerializeMap = serde::ser::Impossible<Captured, Self::Error>;
+        type SerializeStruct = serde::ser::Impossible<Captured, Self::Error>;
+        type SerializeStructVariant = serde::ser::Impossible<Captured, Self::Error>;
+
+        fn is_human_readable(&self) -> bool {
+            return self.human_readable;
+        }
+
+        fn serialize_str(self, s: &str) -> Result<Captured, Self::Error> {
+            return Ok(Captured::Str(s.to_string()));
+        }
+        fn serialize_bytes(self, bs: &[u8]) -> Result<Captured, Self::Error> {
+            return Ok(Captured::Bytes(bs.to_vec()));
+        }
+
+        capture_unsupported!(
+            serialize_bool(bool),
+            serialize_i8(i8),
+            serialize_i16(i16),
+            serialize_i32(i32),
+            serialize_i64(i64),
+            serialize_u8(u8),
+            serialize_u16(u16),
+            serialize_u32(u32),
+            serialize_u64(u64),
+            serialize_f32(f32),
+            serialize_f64(f64),
+            serialize_char(char),
+            serialize_none(),
+            serialize_unit(),
+            serialize_unit_struct(&'static str),
+            serialize_unit_variant(&'static str, u32, &'static str),
+        );
+
+        fn serialize_some<T: ?Sized + serde::Serialize>(
+            self,
+            _: &T,
+        ) -> Result<Captured, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
+            self,
+            _: &'static str,
+            _: &T,
+        ) -> Result<Captured, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: &T,
+        ) -> Result<Captured, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_tuple_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_tuple_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStruct, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+        fn serialize_struct_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStructVariant, Self::Error> {
+            return Err(serde::ser::Error::custom("unsupported"));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use serde::de::value::{BytesDeserializer, Error, StrDeserializer};
+        use serde::{Deserialize, Serialize};
+
+        let mut rng = TestRng::new(2742);
+        for u in [U256::zero(), U256::one(), U256::MAX]
+            .into_iter()
+            .chain((0..8).map(|_| U256::random(&mut rng)))
+        {
+            let text = u
+                .serialize(Capture {
+                    human_readable: true,
+                })
+                .unwrap();
+            assert_eq!(text, Captured::Str(format!("0x{}", u)));
+            if let Captured::Str(s) = text {
+                let back: Result<U256, Error> = U256::deserialize(StrDeserializer::new(&s));
+                assert_eq!(back.unwrap(), u);
+            }
+
+            let binary = u
+                .serialize(Capture {
+                    human_readable: false,
+                })
+                .unwrap();
+            assert_eq!(binary, Captured::Bytes(u.to_be_bytes().to_vec()));
+            if let Captured::Bytes(bs) = binary {
+                let back: Result<U256, Error> = U256::deserialize(BytesDeserializer::new(&bs));
+                assert_eq!(back.unwrap(), u);
+            }
+        }
+    }
+
+    /// The full RLP item for an integer's bytes.
+    fn rlp_item(bs: &[u8]) -> Vec<u8> {
+        if bs.len() == 1 && bs[0] < 0x80 {
+            return bs.to_vec();
+        }
+        let mut r = vec![0x80 + bs.len() as u8];
+        r.extend(bs);
+        return r;
+    }
+
+    #[test]
+    fn rlp_bytes_vectors() {
+        // ethereum/tests RLPTests/rlptest.json, plus the 0x7f/0x80 boundary
+        // and a full 32 bytes
+        let vectors = [
+            ("0", "80"),
+            ("1", "01"),
+            ("7f", "7f"),
+            ("80", "8180"),
+            ("64", "64"),
+            ("3e8", "8203e8"),
+            ("186a0", "830186a0"),
+            (
+                "102030405060708090a0b0c0d0e0f2",
+                "8f102030405060708090a0b0c0d0e0f2",
+            ),
+            (
+                "100102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "a0100102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            ),
+        ];
+        for (n, item) in vectors {
+            let u = U256::from_str(n).unwrap();
+            let bs = u.to_rlp_bytes();
+            assert_eq!(crate::base16::encode_bytes(&rlp_item(&bs)), item, "{}", n);
+            assert_eq!(U256::from_rlp_bytes(&bs), Ok(u));
+        }
+
+        assert!(U256::zero().to_rlp_bytes().is_empty());
+        let max = U256::from_limbs([u64::MAX; 4]);
+        assert_eq!(max.to_rlp_bytes(), [0xff; 32]);
+        assert_eq!(U256::from_rlp_bytes(&[0xff; 32]), Ok(max));
+    }
+
+    #[test]
+    fn rlp_bytes_errors() {
+        assert_eq!(U256::from_rlp_bytes(&[0]), Err(RlpIntError::LeadingZero));
+        assert_eq!(
+            U256::from_rlp_bytes(&[0, 0x80]),
+            Err(RlpIntError::LeadingZero)
+        );
+        assert_eq!(
+            U256::from_rlp_bytes(&[1; 33]),
+            Err(RlpIntError::TooLong { max: 32, got: 33 })
+        );
+        assert_eq!(
+            u256::u64_from_rlp_bytes(&[1; 9]),
+            Err(RlpIntError::TooLong { max: 8, got: 9 })
+        );
+        assert_eq!(
+            u256::u128_from_rlp_bytes(&[0, 1]),
+            Err(RlpIntError::LeadingZero)
+        );
+    }
+
+    #[test]
+    fn rlp_bytes_small_ints() {
+        for n in [0, 1, 0x7f, 0x80, 0xff, 0x100, u64::MAX >> 8, u64::MAX] {
+            let bs = u256::u64_to_rlp_bytes(n);
+            assert_eq!(bs, U256::from(n).to_rlp_bytes());
+            assert_eq!(u256::u64_from_rlp_bytes(&bs), Ok(n));
+        }
+        for n in [0, 0x80, u64::MAX as u128 + 1, u128::MAX] {
+            let bs = u256::u128_to_rlp_bytes(n);
+            assert_eq!(bs, U256::from(n).to_rlp_bytes());
+            assert_eq!(u256::u128_from_rlp_bytes(&bs), Ok(n));
+        }
+        assert_eq!(u256::u64_to_rlp_bytes(0x0400), [4, 0]);
+    }
+
+    #[test]
+    fn random_below_bounds() {
+        let mut rng = TestRng::new(2762);
+
+        let mut seen = [false; 10];
+        for _ in 0..1000 {
+            let r = U256::random_below(&mut rng, &U256::new(10));
+            assert!(r < U256::new(10));
+            seen[u64::try_from(r).unwrap() as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+
+        for _ in 0..100 {
+            assert!(U256::random_below(&mut rng, &U256::one()).is_zero());
+        }
+
+        // Just over a power of two, where most draws are rejected
+        let half = U256 {
+            v: (U256::one() << 255).v + U256::one().v,
+        };
+        let n = crate::secp256k1::SECP256K1::n();
+        let mut high = 0;
+        for _ in 0..2000 {
+            assert!(U256::random_below(&mut rng, &half) < half);
+            let k = U256::random_below(&mut rng, &n);
+            assert!(k < n);
+            high += k.bit(255) as u32;
+        }
+        assert!((800..1200).contains(&high), "{}", high);
+    }
+
+    #[test]
+    fn random_all_bits() {
+        let mut rng = TestRng::new(276);
+        let mut or = U256::zero();
+        let mut and = U256::from_limbs([u64::MAX; 4]);
+        for _ in 0..64 {
+            let r = U256::random(&mut rng);
+            or = U256 { v: or.v | r.v };
+            and = U256 { v: and.v & r.v };
+        }
+        assert_eq!(or, U256::from_limbs([u64::MAX; 4]));
+        assert!(and.is_zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "nonzero-bound")]
+    fn random_below_zero() {
+        U256::random_below(&mut TestRng::new(0), &U256::zero());
+    }
+
+    /// The double-and-add `mul_mod` this crate used before the widening
+    /// multiply, kept as a reference.
+    fn mul_mod_double_and_add(a: &U256, b: &U256, p: &U256) -> U256 {
+        let (x1, x2) = (U256 { v: a.v % p.v }, U256 { v: b.v % p.v });
+        let (seq, adder) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
+
+        let mut base = U256::zero();
+        let mut on = false;
+        for i in (0..256).rev() {
+            if on {
+                base = base.add_mod(&base, p);
+            }
+            if seq.bit(i) {
+                on = true;
+                base = base.add_mod(&adder, p);
+            }
+        }
+        return base;
+    }
+
+    #[test]
+    fn mul_mod_matches_double_and_add() {
+        let mut rng = TestRng::new(2772);
+        let moduli = [
+            crate::secp256k1::SECP256K1::p(),
+            crate::secp256k1::SECP256K1::n(),
+            U256::from_limbs([u64::MAX; 4]),
+            U256::new(1),
+            U256::new(2),
+            U256::new(0xfffffffb),
+        ];
+        for p in moduli {
+            for _ in 0..200 {
+                let a = U256::random(&mut rng);
+                let b = U256::random(&mut rng) >> (rng.next_u32() % 257);
+                assert_eq!(
+                    a.mul_mod(&b, &p),
+                    mul_mod_double_and_add(&a, &b, &p),
+                    "{} * {} mod {}",
+                    a,
+                    b,
+                    p
+                );
+            }
+        }
+
+        assert_eq!(
+            U256::one().try_mul_mod(&U256::one(), &U256::zero()),
+            Err(ArithError::ZeroModulus)
+        );
+    }
 }
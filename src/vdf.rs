@@ -0,0 +1,222 @@
+//! Iterated squaring mod the secp256k1 field prime, with checkpoints.
+//!
+//! This is only the sequential core of a time-lock: x^(2^t) takes t
+//! squarings to compute, but with a checkpoint every k squarings anyone can
+//! check the work segment by segment, in parallel. It is not a Wesolowski or
+//! Pietrzak VDF, there is no succinct proof, and p is public so x^(2^t) can
+//! be shortcut through the exponent 2^t mod (p - 1).
+
+use crate::modctx::ModCtx;
+use crate::secp256k1::SECP256K1;
+use crate::u256::U256;
+use rand::seq::index;
+use std::thread;
+
+/// Segments re-checked by `verify_checkpoints`, besides the final one.
+pub const SAMPLED_SEGMENTS: usize = 16;
+
+/// x^(2^iterations) mod p, and x^(2^(i·checkpoint_every)) for every
+/// i ≥ 1 up to iterations. None for a zero interval, which
+/// `verify_checkpoints` rejects too.
+pub fn iterated_square(
+    x: &U256,
+    iterations: u64,
+    checkpoint_every: u64,
+) -> Option<(U256, Vec<U256>)> {
+    if checkpoint_every == 0 {
+        return None;
+    }
+
+    let f = SECP256K1::field();
+    let mut y = f.add(x, &U256::zero());
+    // Grown as the squarings happen, not sized up front from the arguments
+    let mut checkpoints = vec![];
+    for i in 1..=iterations {
+        y = f.mul(&y, &y);
+        if i % checkpoint_every == 0 {
+            checkpoints.push(y);
+        }
+    }
+
+    return Some((y, checkpoints));
+}
+
+/// Re-does `SAMPLED_SEGMENTS` random checkpoint-to-checkpoint segments, plus
+/// the last one up to `result`, each on its own thread. False if the
+/// checkpoint count is wrong or any checked segment does not match. With
+/// no more segments than the sample size every one is checked.
+pub fn verify_checkpoints(
+    x: &U256,
+    checkpoints: &[U256],
+    iterations: u64,
+    checkpoint_every: u64,
+    result: &U256,
+) -> bool {
+    let segments = match segments(x, checkpoints, iterations, checkpoint_every, result) {
+        Some(s) => s,
+        None => return false,
+    };
+
+    // The last segment is always checked so that `result` is covered
+    let last = segments.len() - 1;
+    let sample = index::sample(&mut rand::thread_rng(), last, SAMPLED_SEGMENTS.min(last));
+
+    let f = SECP256K1::field();
+    return thread::scope(|s| {
+        let handles: Vec<_> = sample
+            .iter()
+            .chain([last])
+            .map(|i| {
+                let segment = &segments[i];
+                return s.spawn(move || check_segment(f, segment));
+            })
+            .collect();
+
+        return handles
+            .into_iter()
+            .all(|h| h.join().expect("segment-thread"));
+    });
+}
+
+/// (start, squarings, end), one per checkpoint and a final one ending at
+/// the result, which may be empty.
+type Segment = (U256, u64, U256);
+
+fn segments(
+    x: &U256,
+    checkpoints: &[U256],
+    iterations: u64,
+    checkpoint_every: u64,
+    result: &U256,
+) -> Option<Vec<Segment>> {
+    if checkpoint_every == 0 || checkpoints.len() as u64 != iterations / checkpoint_every {
+        return None;
+    }
+
+    let mut segments = Vec::with_capacity(checkpoints.len() + 1);
+    let mut start = *x;
+    for c in checkpoints {
+        segments.push((start, checkpoint_every, *c));
+        start = *c;
+    }
+    segments.push((start, iterations % checkpoint_every, *result));
+
+    return Some(segments);
+}
+
+fn check_segment(f: &ModCtx, segment: &Segment) -> bool {
+    let (start, squarings, end) = segment;
+    let mut y = f.add(start, &U256::zero());
+    for _ in 0..*squarings {
+        y = f.mul(&y, &y);
+    }
+    return y == *end;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::secp256k1::SECP256K1;
+    use crate::u256::U256;
+    use crate::vdf::*;
+
+    /// Every segment, one after another.
+    fn verify_sequential(
+        x: &U256,
+        checkpoints: &[U256],
+        iterations: u64,
+        checkpoint_every: u64,
+        result: &U256,
+    ) -> bool {
+        let f = SECP256K1::field();
+        return match segments(x, checkpoints, iterations, checkpoint_every, result) {
+            Some(s) => s.iter().all(|segment| check_segment(f, segment)),
+            None => false,
+        };
+    }
+
+    #[test]
+    fn iterated_square_small() {
+        let x = U256::from(3u8);
+        let p = SECP256K1::p();
+
+        // 3^(2^10) = 3^1024
+        let (y, checkpoints) = iterated_square(&x, 10, 4).unwrap();
+        assert_eq!(y, x.exp_mod(&U256::from(1024u32), &p));
+        assert_eq!(
+            checkpoints,
+            [
+                x.exp_mod(&U256::from(1u32 << 4), &p),
+                x.exp_mod(&U256::from(1u32 << 8), &p)
+            ]
+        );
+
+        let (y, checkpoints) = iterated_square(&x, 0, 5).unwrap();
+        assert_eq!(y, x);
+        assert!(checkpoints.is_empty());
+
+        assert_eq!(iterated_square(&x, 10, 0), None);
+
+        // Inputs are reduced mod p first
+        let (y, _) = iterated_square(&p, 3, 1).unwrap();
+        assert!(y.is_zero());
+    }
+
+    #[test]
+    fn verify_checkpoints_exhaustive() {
+        let x = U256::from(0xc0ffeeu32);
+        for (iterations, every) in [(0, 1), (1, 1), (12, 3), (13, 3), (40, 7), (5, 10)] {
+            let (y, checkpoints) = iterated_square(&x, iterations, every).unwrap();
+            assert!(verify_checkpoints(&x, &checkpoints, iterations, every, &y));
+            assert!(verify_sequential(&x, &checkpoints, iterations, every, &y));
+
+            // Wrong iteration count or interval
+            assert!(!verify_checkpoints(
+                &x,
+                &checkpoints,
+                iterations + every,
+                every,
+                &y
+            ));
+            assert!(!verify_checkpoints(&x, &checkpoints, iterations, 0, &y));
+        }
+    }
+
+    #[test]
+    fn verify_checkpoints_tampered() {
+        let x = U256::from(5u8);
+        let (y, checkpoints) = iterated_square(&x, 64, 4).unwrap();
+        assert_eq!(checkpoints.len(), SAMPLED_SEGMENTS);
+
+        for i in 0..checkpoints.len() {
+            let mut tampered = checkpoints.clone();
+            tampered[i] = tampered[i].add_mod(&U256::one(), &SECP256K1::p());
+            assert!(!verify_checkpoints(&x, &tampered, 64, 4, &y), "{}", i);
+            assert!(!verify_sequential(&x, &tampered, 64, 4, &y));
+        }
+
+        let wrong = y.add_mod(&U256::one(), &SECP256K1::p());
+        assert!(!verify_checkpoints(&x, &checkpoints, 64, 4, &wrong));
+        assert!(!verify_checkpoints(
+            &U256::from(6u8),
+            &checkpoints,
+            64,
+            4,
+            &y
+        ));
+    }
+
+    #[test]
+    fn verify_checkpoints_sampled() {
+        // More segments than the sample, the result is still always checked
+        let x = U256::from(7u8);
+        let (y, checkpoints) = iterated_square(&x, 200, 2).unwrap();
+        assert!(verify_checkpoints(&x, &checkpoints, 200, 2, &y));
+        assert_eq!(
+            verify_checkpoints(&x, &checkpoints, 200, 2, &y),
+            verify_sequential(&x, &checkpoints, 200, 2, &y)
+        );
+
+        let wrong = y.add_mod(&U256::one(), &SECP256K1::p());
+        assert!(!verify_checkpoints(&x, &checkpoints, 200, 2, &wrong));
+    }
+}
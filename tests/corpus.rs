@@ -0,0 +1,96 @@
+//! Replays the regression corpus in tests/corpus/. Each subdirectory is
+//! named after a decoder in `DECODERS` and holds one hex-encoded input per
+//! file, e.g. a fuzzer crasher. Every input must come back as Ok or a typed
+//! error, never a panic. Add cases with
+//! `cargo run --example add_corpus -- <decoder> <hexfile>`.
+
+#![allow(clippy::needless_return)]
+
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::str::{self, FromStr};
+
+use eccsecp256k1::crypto::ethereum;
+use eccsecp256k1::secp256k1::EccPoint;
+use eccsecp256k1::u256::U256;
+
+/// Inputs are capped so the whole corpus replays in well under a second.
+const MAX_INPUT_LEN: usize = 4096;
+
+/// Decodes the input and discards the value, keeping only the error.
+type Decoder = fn(&[u8]) -> Result<(), String>;
+
+const DECODERS: [(&str, Decoder); 7] = [
+    ("u256_hex", |b| {
+        let s = str::from_utf8(b).map_err(|e| format!("{:?}", e))?;
+        return U256::from_str(s).map(drop).map_err(|e| format!("{:?}", e));
+    }),
+    ("u256_dec", |b| {
+        let s = str::from_utf8(b).map_err(|e| format!("{:?}", e))?;
+        return U256::from_dec_str(s)
+            .map(drop)
+            .map_err(|e| format!("{:?}", e));
+    }),
+    ("u256_be_bytes", |b| {
+        return U256::try_from(b).map(drop).map_err(|e| format!("{:?}", e));
+    }),
+    ("u256_rlp", |b| {
+        return U256::from_rlp_bytes(b)
+            .map(drop)
+            .map_err(|e| format!("{:?}", e));
+    }),
+    ("eth_address", |b| {
+        let s = str::from_utf8(b).map_err(|e| format!("{:?}", e))?;
+        return ethereum::address_from_any(s)
+            .map(drop)
+            .map_err(|e| format!("{:?}", e));
+    }),
+    ("spki_der", |b| {
+        return EccPoint::from_spki_der(b)
+            .map(drop)
+            .map_err(|e| format!("{:?}", e));
+    }),
+    ("cose_key", |b| {
+        return EccPoint::from_cose_key(b)
+            .map(drop)
+            .map_err(|e| format!("{:?}", e));
+    }),
+];
+
+fn decoder(name: &str) -> Option<Decoder> {
+    return DECODERS.iter().find(|(n, _)| *n == name).map(|(_, d)| *d);
+}
+
+#[test]
+fn corpus_replay() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut replayed = 0;
+
+    for dir in fs::read_dir(&root).expect("corpus-dir") {
+        let dir = dir.expect("corpus-dir").path();
+        let name = dir.file_name().unwrap().to_str().unwrap().to_string();
+        let decode = decoder(&name).unwrap_or_else(|| panic!("no decoder named {}", name));
+
+        for file in fs::read_dir(&dir).expect("corpus-dir") {
+            let file = file.expect("corpus-file").path();
+            let text = fs::read_to_string(&file).expect("corpus-file");
+            let input = hex::decode(text.trim()).expect("corpus-hex");
+            assert!(input.len() <= MAX_INPUT_LEN, "{:?} is too long", file);
+
+            let r = catch_unwind(AssertUnwindSafe(|| decode(&input)));
+            assert!(r.is_ok(), "{} panicked on {:?}", name, file);
+            replayed += 1;
+        }
+    }
+
+    assert!(replayed > 0);
+}
+
+#[test]
+fn corpus_dirs_cover_decoders() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    for (name, _) in DECODERS {
+        assert!(root.join(name).is_dir(), "no corpus for {}", name);
+    }
+}
@@ -1,24 +1,18 @@
 use std::str::FromStr;
 
-use eccsecp256k1::{base16::*, secp256k1::*, u256::U256};
+use eccsecp256k1::{base16::*, rng::TestRng, secp256k1::*, u256::U256};
 
-use rand::prelude::*;
 use secp256k1::{PublicKey, Secp256k1, SecretKey};
 
 #[test]
-#[ignore]
 fn ecc() {
     // generate a random private key
-    let mut rng = rand::thread_rng();
-    let bytes = [0; 32];
-    let random_bytes = bytes
-        .into_iter()
-        .map(|_| rng.gen_range(0..=255))
-        .collect::<Vec<u8>>();
-    let pr_n = hex::encode(random_bytes);
+    let mut rng = TestRng::new(0x5ec9256b1);
+    let pr = U256::random_below(&mut rng, &SECP256K1::n());
+    let pr_n = pr.to_string();
 
     // generate public key with custom-wrote curve arithmetics
-    let pub_key1 = SECP256K1::pr_to_pub(&U256::from_str(&pr_n).unwrap());
+    let pub_key1 = SECP256K1::pr_to_pub(&pr);
     let mut pub_key_str1 = pub_key1.to_hex_string();
     pub_key_str1.retain(|c| !c.is_whitespace());
     pub_key_str1 = "04".to_owned() + &pub_key_str1;
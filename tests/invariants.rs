@@ -0,0 +1,210 @@
+//! The algebraic laws modular arithmetic must satisfy, checked over
+//! thousands of generated values for both backends, the `U256` `*_mod`
+//! methods and the Montgomery `ModCtx`, and across them. Values come from
+//! `TestRng::interesting_u256`, so limb boundaries and values next to the
+//! modulus show up far more often than uniformly.
+
+#![allow(clippy::needless_return)]
+
+use eccsecp256k1::modctx::ModCtx;
+use eccsecp256k1::rng::TestRng;
+use eccsecp256k1::secp256k1::SECP256K1;
+use eccsecp256k1::u256::U256;
+
+use rand::RngCore;
+
+const CASES: usize = 1000;
+/// Each one several full-width exponentiations, so fewer.
+const EXP_CASES: usize = 100;
+
+/// One implementation of arithmetic mod a fixed modulus.
+trait Backend {
+    fn modulus(&self) -> U256;
+    fn add(&self, a: &U256, b: &U256) -> U256;
+    fn sub(&self, a: &U256, b: &U256) -> U256;
+    fn mul(&self, a: &U256, b: &U256) -> U256;
+    fn exp(&self, a: &U256, e: &U256) -> U256;
+    fn inv(&self, a: &U256) -> Option<U256>;
+}
+
+struct Methods(U256);
+
+impl Backend for Methods {
+    fn modulus(&self) -> U256 {
+        return self.0;
+    }
+    fn add(&self, a: &U256, b: &U256) -> U256 {
+        return a.add_mod(b, &self.0);
+    }
+    fn sub(&self, a: &U256, b: &U256) -> U256 {
+        return a.sub_mod(b, &self.0);
+    }
+    fn mul(&self, a: &U256, b: &U256) -> U256 {
+        return a.mul_mod(b, &self.0);
+    }
+    fn exp(&self, a: &U256, e: &U256) -> U256 {
+        return a.exp_mod(e, &self.0);
+    }
+    fn inv(&self, a: &U256) -> Option<U256> {
+        return a.inv_mod(&self.0);
+    }
+}
+
+impl Backend for ModCtx {
+    fn modulus(&self) -> U256 {
+        return ModCtx::modulus(self);
+    }
+    fn add(&self, a: &U256, b: &U256) -> U256 {
+        return ModCtx::add(self, a, b);
+    }
+    fn sub(&self, a: &U256, b: &U256) -> U256 {
+        return ModCtx::sub(self, a, b);
+    }
+    fn mul(&self, a: &U256, b: &U256) -> U256 {
+        return ModCtx::mul(self, a, b);
+    }
+    fn exp(&self, a: &U256, e: &U256) -> U256 {
+        return ModCtx::exp(self, a, e);
+    }
+    fn inv(&self, a: &U256) -> Option<U256> {
+        return ModCtx::inv(self, a);
+    }
+}
+
+/// Odd, so `ModCtx` takes them too. The last two are composite.
+fn moduli() -> Vec<(U256, bool)> {
+    return vec![
+        (SECP256K1::p(), true),
+        (SECP256K1::n(), true),
+        (
+            U256::from_hex("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed")
+                .unwrap(),
+            true,
+        ),
+        (U256::new(0xfffffffb), true),
+        (U256::from_limbs([u64::MAX; 4]), false),
+        (U256::new(0x10001 * 0x3b), false),
+    ];
+}
+
+fn backends() -> Vec<(Box<dyn Backend>, bool)> {
+    let mut r: Vec<(Box<dyn Backend>, bool)> = vec![];
+    for (m, prime) in moduli() {
+        r.push((Box::new(Methods(m)), prime));
+        r.push((Box::new(ModCtx::new(m).expect("odd-modulus")), prime));
+    }
+    return r;
+}
+
+fn reduce(f: &dyn Backend, a: &U256) -> U256 {
+    return f.add(a, &U256::zero());
+}
+
+#[test]
+fn invariants_ring_laws() {
+    let mut rng = TestRng::new(2781);
+    for (f, _) in backends() {
+        let (zero, one, m) = (U256::zero(), U256::one(), f.modulus());
+        for _ in 0..CASES {
+            let a = rng.interesting_u256(&m);
+            let b = rng.interesting_u256(&m);
+            let c = rng.interesting_u256(&m);
+            let ctx = format!("{} {} {} mod {}", a, b, c, m);
+
+            assert!(f.add(&a, &b) < m, "{}", ctx);
+            assert!(f.mul(&a, &b) < m, "{}", ctx);
+
+            assert_eq!(f.add(&a, &b), f.add(&b, &a), "{}", ctx);
+            assert_eq!(f.mul(&a, &b), f.mul(&b, &a), "{}", ctx);
+            assert_eq!(
+                f.add(&f.add(&a, &b), &c),
+                f.add(&a, &f.add(&b, &c)),
+                "{}",
+                ctx
+            );
+            assert_eq!(
+                f.mul(&f.mul(&a, &b), &c),
+                f.mul(&a, &f.mul(&b, &c)),
+                "{}",
+                ctx
+            );
+            assert_eq!(
+                f.mul(&a, &f.add(&b, &c)),
+                f.add(&f.mul(&a, &b), &f.mul(&a, &c)),
+                "{}",
+                ctx
+            );
+
+            assert_eq!(f.mul(&a, &one), reduce(&*f, &a), "{}", ctx);
+            assert!(f.mul(&a, &zero).is_zero(), "{}", ctx);
+            assert!(f.sub(&a, &a).is_zero(), "{}", ctx);
+            assert_eq!(f.add(&f.sub(&a, &b), &b), reduce(&*f, &a), "{}", ctx);
+            assert!(f.add(&a, &f.sub(&zero, &a)).is_zero(), "{}", ctx);
+        }
+    }
+}
+
+#[test]
+fn invariants_inverses_and_powers() {
+    let mut rng = TestRng::new(2782);
+    for (f, prime) in backends() {
+        let (one, m) = (U256::one(), f.modulus());
+        let m_1 = m.sub_mod(&one, &U256::from_limbs([u64::MAX; 4]));
+        let half = m_1 >> 1;
+
+        for _ in 0..EXP_CASES {
+            let a = rng.interesting_u256(&m);
+            let ctx = format!("{} mod {}", a, m);
+
+            match f.inv(&a) {
+                Some(i) => assert_eq!(f.mul(&a, &i), one, "{}", ctx),
+                None => assert!(!prime || reduce(&*f, &a).is_zero(), "{}", ctx),
+            }
+
+            let mut power = one;
+            for k in 0..8u64 {
+                assert_eq!(f.exp(&a, &U256::new(k)), power, "{}^{}", ctx, k);
+                power = f.mul(&power, &a);
+            }
+            // Small enough that e1 + e2 does not wrap
+            let (e1, e2) = (U256::random(&mut rng) >> 1, U256::new(rng.next_u64()));
+            assert_eq!(
+                f.mul(&f.exp(&a, &e1), &f.exp(&a, &e2)),
+                f.exp(&a, &e1.add_mod(&e2, &U256::from_limbs([u64::MAX; 4]))),
+                "{}",
+                ctx
+            );
+
+            if prime && !reduce(&*f, &a).is_zero() {
+                // Fermat, and Euler's criterion
+                assert_eq!(f.exp(&a, &m_1), one, "{}", ctx);
+                let euler = f.exp(&a, &half);
+                let expected = match a.legendre(&m) {
+                    1 => one,
+                    _ => f.sub(&U256::zero(), &one),
+                };
+                assert_eq!(euler, expected, "{}", ctx);
+            }
+        }
+    }
+}
+
+#[test]
+fn invariants_backends_agree() {
+    let mut rng = TestRng::new(2783);
+    for (m, _) in moduli() {
+        let methods = Methods(m);
+        let ctx = ModCtx::new(m).expect("odd-modulus");
+        for i in 0..CASES {
+            let a = rng.interesting_u256(&m);
+            let b = rng.interesting_u256(&m);
+            assert_eq!(methods.add(&a, &b), Backend::add(&ctx, &a, &b));
+            assert_eq!(methods.sub(&a, &b), Backend::sub(&ctx, &a, &b));
+            assert_eq!(methods.mul(&a, &b), Backend::mul(&ctx, &a, &b));
+            if i < EXP_CASES {
+                assert_eq!(methods.exp(&a, &b), Backend::exp(&ctx, &a, &b));
+                assert_eq!(methods.inv(&a), Backend::inv(&ctx, &a));
+            }
+        }
+    }
+}
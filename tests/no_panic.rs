@@ -0,0 +1,226 @@
+//! Feeds arbitrary bytes and strings into every entry point that takes
+//! untrusted input and asserts none of them panic. Parsers must return an
+//! error, point arithmetic must return some point.
+
+#![allow(clippy::needless_return)]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::str::FromStr;
+
+use eccsecp256k1::asn1::DerReader;
+use eccsecp256k1::crypto::ethereum;
+use eccsecp256k1::rng::TestRng;
+use eccsecp256k1::secp256k1::{EccPoint, SECP256K1};
+use eccsecp256k1::u256::{ArithError, U256};
+
+use rand::RngCore;
+
+const CASES: usize = 2000;
+
+fn assert_no_panic<F: FnOnce()>(input: &dyn std::fmt::Debug, f: F) {
+    let r = catch_unwind(AssertUnwindSafe(f));
+    assert!(r.is_ok(), "panicked on input {:?}", input);
+}
+
+fn arbitrary_bytes(rng: &mut TestRng, max_len: u32) -> Vec<u8> {
+    let mut bytes = vec![0; (rng.next_u32() % (max_len + 1)) as usize];
+    rng.fill_bytes(&mut bytes);
+    return bytes;
+}
+
+/// Mostly hex-ish characters, with some multi-byte chars, prefixes and
+/// whitespace mixed in to hit the boundary cases.
+fn arbitrary_string(rng: &mut TestRng, max_len: u32) -> String {
+    const ALPHABET: [&str; 12] = [
+        "0", "7", "a", "F", "x", "0x", "g", " ", "é", "€", "\u{0}", "(",
+    ];
+    let len = rng.next_u32() % (max_len + 1);
+    return (0..len)
+        .map(|_| ALPHABET[(rng.next_u32() % ALPHABET.len() as u32) as usize])
+        .collect();
+}
+
+fn arbitrary_u256(rng: &mut TestRng) -> U256 {
+    let bytes = arbitrary_bytes(rng, 32);
+    return U256::from_be_bytes(&bytes);
+}
+
+#[test]
+fn no_panic_u256_from_str() {
+    let mut rng = TestRng::new(1);
+    for _ in 0..CASES {
+        let s = arbitrary_string(&mut rng, 80);
+        assert_no_panic(&s, || {
+            let _ = U256::from_str(&s);
+        });
+    }
+}
+
+#[test]
+fn no_panic_address_parsing() {
+    let mut rng = TestRng::new(2);
+    for _ in 0..CASES {
+        let mut s = arbitrary_string(&mut rng, 50);
+        if rng.next_u32() & 1 == 0 {
+            s = format!("0x{}", s);
+        }
+        assert_no_panic(&s, || {
+            let _ = ethereum::check_sum_with_chain_id(&s, None);
+            let _ = ethereum::check_sum_with_chain_id(&s, Some(30));
+            let _ = ethereum::is_valid_checksum_with_chain_id(&s, Some(31));
+            let _ = ethereum::check_sum(&s);
+            let _ = ethereum::derive_address(&s);
+        });
+    }
+}
+
+#[test]
+fn no_panic_public_key_and_point_hex() {
+    let mut rng = TestRng::new(7);
+    let valid = ethereum::derive_address(&format!("04{}", "ab".repeat(64)));
+    assert!(valid.is_ok());
+    for _ in 0..CASES {
+        // Around the 130-character length derive_address wants
+        let mut s = format!("04{}", arbitrary_string(&mut rng, 130));
+        s.truncate(s.char_indices().nth(130).map_or(s.len(), |(i, _)| i));
+        let (x, y) = (
+            arbitrary_string(&mut rng, 70),
+            arbitrary_string(&mut rng, 70),
+        );
+        assert_no_panic(&(&s, &x, &y), || {
+            let _ = ethereum::derive_address(&s);
+            let _ = EccPoint::from_hex_coordinates(&x, &y);
+            let _ = EccPoint::from_hex_string(&s);
+        });
+    }
+}
+
+#[test]
+fn no_panic_abi_signature_parsing() {
+    let mut rng = TestRng::new(3);
+    let types = ["uint", "int8", "bytes", "string", "[]", "[2]", ",", ")"];
+    for _ in 0..CASES {
+        let mut s = arbitrary_string(&mut rng, 10);
+        for _ in 0..rng.next_u32() % 4 {
+            s.push_str(types[(rng.next_u32() % types.len() as u32) as usize]);
+        }
+        assert_no_panic(&s, || {
+            let _ = ethereum::function_selector(&s);
+            let _ = ethereum::abi_encode(&s, &[]);
+            let _ = ethereum::abi_encode_params(&s, &[]);
+        });
+    }
+}
+
+#[test]
+fn no_panic_der_and_spki() {
+    let mut rng = TestRng::new(4);
+    let valid = EccPoint::to_spki_der(&SECP256K1::g());
+    for _ in 0..CASES {
+        // Either random bytes or the valid encoding with a few bytes flipped
+        let bytes = if rng.next_u32() & 1 == 0 {
+            arbitrary_bytes(&mut rng, 100)
+        } else {
+            let mut b = valid.clone();
+            for _ in 0..1 + rng.next_u32() % 3 {
+                let i = (rng.next_u32() as usize) % b.len();
+                b[i] = rng.next_u32() as u8;
+            }
+            b.truncate(1 + (rng.next_u32() as usize) % b.len());
+            b
+        };
+        assert_no_panic(&bytes, || {
+            let _ = EccPoint::from_spki_der(&bytes);
+            let mut r = DerReader::new(&bytes);
+            let _ = r.read_integer();
+            let _ = r.read_sequence();
+        });
+    }
+}
+
+#[test]
+fn no_panic_point_arithmetic() {
+    let mut rng = TestRng::new(5);
+    let g = SECP256K1::g();
+    for _ in 0..50 {
+        let pt = EccPoint {
+            x: arbitrary_u256(&mut rng),
+            y: arbitrary_u256(&mut rng),
+        };
+        let input = pt.to_hex_string();
+        assert_no_panic(&input, || {
+            let _ = SECP256K1::is_on_curve(&pt);
+            let _ = SECP256K1::add_points(&pt, &pt);
+            let _ = SECP256K1::add_points(&pt, &g);
+            let _ = SECP256K1::add_points(&pt, &SECP256K1::zero_point());
+            let _ = SECP256K1::double_point(&pt);
+        });
+    }
+
+    // Edge points: the identity and P + (-P)
+    let neg_g = EccPoint {
+        x: g.x,
+        y: g.y.neg_mod(&SECP256K1::p()),
+    };
+    assert_no_panic(&"identity", || {
+        let z = SECP256K1::zero_point();
+        let _ = SECP256K1::add_points(&z, &z);
+        let _ = SECP256K1::add_points(&g, &neg_g);
+    });
+
+    // Unreduced coordinates: the same x mod p written two ways, and y = p.
+    // Only x below 2^256 - p has a second form.
+    let p = SECP256K1::p();
+    for _ in 0..50 {
+        let x1 = U256::new(rng.next_u32() as u64);
+        let x2 = x1.checked_add(&p).expect("fits");
+        let a = EccPoint {
+            x: x1,
+            y: arbitrary_u256(&mut rng),
+        };
+        let b = EccPoint {
+            x: x2,
+            y: arbitrary_u256(&mut rng),
+        };
+        let y_p = EccPoint { x: x1, y: p };
+        let input = format!("{} {}", a.to_hex_string(), b.to_hex_string());
+        assert_no_panic(&input, || {
+            let _ = SECP256K1::add_points(&a, &b);
+            let _ = SECP256K1::add_points(&a, &y_p);
+            let _ = SECP256K1::add_points(&y_p, &y_p);
+            let _ = SECP256K1::double_point(&b);
+            let _ = SECP256K1::double_point(&y_p);
+        });
+    }
+}
+
+#[test]
+fn no_panic_modular_arithmetic() {
+    let mut rng = TestRng::new(6);
+    for _ in 0..50 {
+        let a = arbitrary_u256(&mut rng);
+        let b = arbitrary_u256(&mut rng);
+        let mut p = arbitrary_u256(&mut rng);
+        if rng.next_u32() & 3 == 0 {
+            p = U256::one();
+        }
+        if rng.next_u32() & 7 == 0 {
+            p = U256::zero();
+        }
+        let input = format!("{} {} {}", a, b, p);
+        assert_no_panic(&input, || {
+            let _ = a.try_add_mod(&b, &p);
+            let _ = a.try_sub_mod(&b, &p);
+            let _ = a.try_mul_mod(&b, &p);
+            let _ = a.try_exp_mod(&b, &p);
+            let _ = a.try_div_mod(&b, &p);
+            let _ = a.try_neg_mod(&p);
+            let _ = a.inv_mod(&p);
+        });
+        if p.is_zero() {
+            assert_eq!(a.try_add_mod(&b, &p), Err(ArithError::ZeroModulus));
+            assert_eq!(a.try_neg_mod(&p), Err(ArithError::ZeroModulus));
+            assert_eq!(a.inv_mod(&p), None);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Snapshot of the crate's public items, so that exposing something new or
+//! changing a public signature shows up in review as a fixture diff.
+//!
+//! Each entry is the first line of a `pub` item outside the test modules.
+//! After a deliberate API change, regenerate the fixture with
+//! `UPDATE_PUBLIC_API=1 cargo test --test public_api`.
+
+#![allow(clippy::needless_return)]
+
+const SOURCES: [(&str, &str); 11] = [
+    ("lib.rs", include_str!("../src/lib.rs")),
+    ("asn1.rs", include_str!("../src/asn1.rs")),
+    ("base16.rs", include_str!("../src/base16.rs")),
+    ("cbor.rs", include_str!("../src/cbor.rs")),
+    ("conformance.rs", include_str!("../src/conformance.rs")),
+    ("crypto.rs", include_str!("../src/crypto.rs")),
+    ("modctx.rs", include_str!("../src/modctx.rs")),
+    ("rng.rs", include_str!("../src/rng.rs")),
+    ("secp256k1.rs", include_str!("../src/secp256k1.rs")),
+    ("u256.rs", include_str!("../src/u256.rs")),
+    ("vdf.rs", include_str!("../src/vdf.rs")),
+];
+
+const FIXTURE: &str = "tests/public_api.txt";
+
+fn public_items() -> String {
+    let mut out = String::new();
+    for (file, src) in SOURCES {
+        let src = match src.find("#[cfg(test)]\nmod tests") {
+            Some(i) => &src[..i],
+            None => src,
+        };
+        for line in src.lines().map(str::trim) {
+            if line.starts_with("pub ") {
+                out.push_str(&format!("{}: {}\n", file, line.trim_end_matches(" {")));
+            }
+        }
+    }
+    return out;
+}
+
+#[test]
+fn public_api_snapshot() {
+    let items = public_items();
+    if std::env::var_os("UPDATE_PUBLIC_API").is_some() {
+        std::fs::write(FIXTURE, &items).expect("write-fixture");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(FIXTURE).expect("read-fixture");
+    let added: Vec<&str> = items.lines().filter(|l| !expected.contains(l)).collect();
+    let removed: Vec<&str> = expected.lines().filter(|l| !items.contains(l)).collect();
+    assert!(
+        added.is_empty() && removed.is_empty(),
+        "public API changed\nadded:\n{}\nremoved:\n{}",
+        added.join("\n"),
+        removed.join("\n")
+    );
+}